@@ -0,0 +1,382 @@
+use std::fs;
+use std::io::Read;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::utils::{
+    current_username, env_var_opt, list_dir_sorted, path_string, read_file_optional,
+    run_command_optional,
+};
+
+const FLATPAK_SYSTEM_DIR: &str = "/var/lib/flatpak";
+const NIX_STORE_DIR: &str = "/nix/store";
+const NIX_PROFILES_DIR: &str = "/nix/var/nix/profiles";
+const APPIMAGE_DIRS: [&str; 5] = [
+    "Applications",
+    "AppImages",
+    ".local/bin",
+    "Downloads",
+    "bin",
+];
+
+#[derive(Serialize)]
+pub struct AppSourcesInfo {
+    flatpak: Option<FlatpakInfo>,
+    snap: Option<Vec<SnapPackageInfo>>,
+    nix: Option<NixInfo>,
+    appimages: Vec<AppImageInfo>,
+}
+
+#[derive(Serialize)]
+pub struct FlatpakInfo {
+    installations: Vec<FlatpakInstallationInfo>,
+}
+
+#[derive(Serialize)]
+pub struct FlatpakInstallationInfo {
+    name: String,
+    path: String,
+    remotes: Vec<FlatpakRemoteInfo>,
+    apps: Vec<FlatpakRefInfo>,
+    runtimes: Vec<FlatpakRefInfo>,
+}
+
+#[derive(Serialize)]
+pub struct FlatpakRemoteInfo {
+    name: String,
+    url: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct FlatpakRefInfo {
+    id: String,
+    arch: String,
+    branch: String,
+    origin: Option<String>,
+    version: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct SnapPackageInfo {
+    name: String,
+    version: String,
+    revision: String,
+    channel: Option<String>,
+    publisher: Option<String>,
+    notes: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct NixInfo {
+    profiles: Vec<NixProfileInfo>,
+}
+
+#[derive(Serialize)]
+pub struct NixProfileInfo {
+    path: String,
+    target: Option<String>,
+    current_generation: Option<u32>,
+    generations: Vec<u32>,
+    packages: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct AppImageInfo {
+    path: String,
+    size: u64,
+    appimage_type: Option<u8>,
+    executable: bool,
+}
+
+fn file_name_string(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+fn home_dir() -> Option<PathBuf> {
+    env_var_opt("HOME").map(PathBuf::from)
+}
+
+// Pulls the newest <release version="..."> out of an AppStream metainfo file.
+fn metainfo_version(files_dir: &Path, id: &str) -> Option<String> {
+    let share = files_dir.join("share");
+    let text = [
+        share.join(format!("metainfo/{id}.metainfo.xml")),
+        share.join(format!("metainfo/{id}.appdata.xml")),
+        share.join(format!("appdata/{id}.appdata.xml")),
+    ]
+    .iter()
+    .find_map(read_file_optional)?;
+    let release = &text[text.find("<release ")?..];
+    let value = &release[release.find("version=\"")? + 9..];
+    Some(value[..value.find('"')?].to_string())
+}
+
+// repo/config is an INI file with sections like [remote "flathub"].
+fn flatpak_remotes(install_dir: &Path) -> Vec<FlatpakRemoteInfo> {
+    let text = read_file_optional(install_dir.join("repo/config")).unwrap_or_default();
+    let mut remotes: Vec<FlatpakRemoteInfo> = Vec::new();
+    let mut in_remote = false;
+    for line in text.lines() {
+        let line = line.trim();
+        if let Some(section) = line.strip_prefix('[') {
+            let name = section
+                .strip_prefix("remote \"")
+                .and_then(|rest| rest.strip_suffix("\"]"));
+            in_remote = name.is_some();
+            if let Some(name) = name {
+                remotes.push(FlatpakRemoteInfo {
+                    name: name.to_string(),
+                    url: None,
+                });
+            }
+        } else if in_remote
+            && let Some((key, value)) = line.split_once('=')
+            && key.trim() == "url"
+            && let Some(remote) = remotes.last_mut()
+        {
+            remote.url = Some(value.trim().to_string());
+        }
+    }
+    remotes
+}
+
+// Deployed refs live under {app,runtime}/<id>/<arch>/<branch>/active; the
+// remote each came from is the one holding a matching repo/refs/remotes entry.
+fn flatpak_refs(
+    install_dir: &Path,
+    kind: &str,
+    remotes: &[FlatpakRemoteInfo],
+) -> Vec<FlatpakRefInfo> {
+    let mut refs = Vec::new();
+    for id_dir in list_dir_sorted(install_dir.join(kind)) {
+        let id = file_name_string(&id_dir);
+        for arch_dir in list_dir_sorted(&id_dir).into_iter().filter(|p| p.is_dir()) {
+            let arch = file_name_string(&arch_dir);
+            for branch_dir in list_dir_sorted(&arch_dir) {
+                let active = branch_dir.join("active");
+                if !active.exists() {
+                    continue;
+                }
+                let branch = file_name_string(&branch_dir);
+                let origin = remotes
+                    .iter()
+                    .find(|remote| {
+                        install_dir
+                            .join("repo/refs/remotes")
+                            .join(&remote.name)
+                            .join(kind)
+                            .join(&id)
+                            .join(&arch)
+                            .join(&branch)
+                            .exists()
+                    })
+                    .map(|remote| remote.name.clone());
+                refs.push(FlatpakRefInfo {
+                    version: metainfo_version(&active.join("files"), &id),
+                    id: id.clone(),
+                    arch: arch.clone(),
+                    branch,
+                    origin,
+                });
+            }
+        }
+    }
+    refs
+}
+
+fn flatpak_installation(name: &str, path: PathBuf) -> Option<FlatpakInstallationInfo> {
+    if !path.is_dir() {
+        return None;
+    }
+    let remotes = flatpak_remotes(&path);
+    Some(FlatpakInstallationInfo {
+        name: name.to_string(),
+        apps: flatpak_refs(&path, "app", &remotes),
+        runtimes: flatpak_refs(&path, "runtime", &remotes),
+        path: path_string(&path),
+        remotes,
+    })
+}
+
+fn flatpak_info() -> Option<FlatpakInfo> {
+    let user_dir = home_dir().map(|home| home.join(".local/share/flatpak"));
+    let installations: Vec<FlatpakInstallationInfo> = [
+        flatpak_installation("system", PathBuf::from(FLATPAK_SYSTEM_DIR)),
+        user_dir.and_then(|dir| flatpak_installation("user", dir)),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    (!installations.is_empty()).then_some(FlatpakInfo { installations })
+}
+
+// "Name  Version  Rev  Tracking  Publisher  Notes" columns from `snap list`.
+fn parse_snap_list(output: &str) -> Vec<SnapPackageInfo> {
+    output
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let field = |index: usize| {
+                fields
+                    .get(index)
+                    .filter(|value| **value != "-")
+                    .map(|value| value.to_string())
+            };
+            Some(SnapPackageInfo {
+                name: field(0)?,
+                version: field(1).unwrap_or_default(),
+                revision: field(2).unwrap_or_default(),
+                channel: field(3),
+                publisher: field(4).map(|p| p.trim_end_matches(['✓', '*']).to_string()),
+                notes: field(5)
+                    .map(|notes| notes.split(',').map(String::from).collect())
+                    .unwrap_or_default(),
+            })
+        })
+        .collect()
+}
+
+fn snap_info() -> Option<Vec<SnapPackageInfo>> {
+    run_command_optional("snap", &["list"]).map(|out| parse_snap_list(&out))
+}
+
+// "/nix/store/<hash>-firefox-120.0" -> "firefox-120.0"
+fn nix_store_name(path: &str) -> Option<String> {
+    let name = path.strip_prefix(NIX_STORE_DIR)?.trim_start_matches('/');
+    let (_, name) = name.split_once('-')?;
+    Some(name.to_string())
+}
+
+// New-style profiles carry a manifest.json; classic nix-env profiles only have
+// a user environment whose bin/ entries link into the store.
+fn nix_profile_packages(target: &Path) -> Vec<String> {
+    if let Some(text) = read_file_optional(target.join("manifest.json"))
+        && let Ok(manifest) = serde_json::from_str::<Value>(&text)
+    {
+        let elements: Vec<&Value> = match manifest.get("elements") {
+            Some(Value::Array(items)) => items.iter().collect(),
+            Some(Value::Object(items)) => items.values().collect(),
+            _ => Vec::new(),
+        };
+        return elements
+            .iter()
+            .filter_map(|element| element.get("storePaths")?.as_array()?.first()?.as_str())
+            .filter_map(nix_store_name)
+            .collect();
+    }
+    let mut packages: Vec<String> = list_dir_sorted(target.join("bin"))
+        .into_iter()
+        .filter_map(|bin| fs::read_link(bin).ok())
+        .filter_map(|link| nix_store_name(&link.to_string_lossy()))
+        .filter_map(|name| name.split('/').next().map(String::from))
+        .collect();
+    packages.sort();
+    packages.dedup();
+    packages
+}
+
+fn nix_profile(path: PathBuf) -> Option<NixProfileInfo> {
+    let link = fs::read_link(&path).ok()?;
+    let dir = path.parent()?.to_path_buf();
+    let base = file_name_string(&path);
+    let link_target = |link: &Path| {
+        if link.is_absolute() {
+            link.to_path_buf()
+        } else {
+            dir.join(link)
+        }
+    };
+    // Generations are siblings named "<profile>-<N>-link".
+    let generation_of = |name: &str| -> Option<u32> {
+        name.strip_prefix(&format!("{base}-"))?
+            .strip_suffix("-link")?
+            .parse()
+            .ok()
+    };
+    let generations: Vec<u32> = {
+        let mut generations: Vec<u32> = list_dir_sorted(&dir)
+            .iter()
+            .filter_map(|entry| generation_of(&file_name_string(entry)))
+            .collect();
+        generations.sort_unstable();
+        generations
+    };
+    let current_generation = generation_of(&file_name_string(&link));
+    let target = fs::canonicalize(link_target(&link)).ok();
+    Some(NixProfileInfo {
+        path: path_string(&path),
+        packages: target
+            .as_deref()
+            .map(nix_profile_packages)
+            .unwrap_or_default(),
+        target: target.map(path_string),
+        current_generation,
+        generations,
+    })
+}
+
+fn nix_info() -> Option<NixInfo> {
+    if !Path::new(NIX_STORE_DIR).is_dir() {
+        return None;
+    }
+    let user = current_username();
+    let mut candidates = vec![
+        PathBuf::from(NIX_PROFILES_DIR).join("default"),
+        PathBuf::from(NIX_PROFILES_DIR).join("system"),
+        PathBuf::from(NIX_PROFILES_DIR).join(format!("per-user/{user}/profile")),
+    ];
+    if let Some(home) = home_dir() {
+        candidates.push(home.join(".local/state/nix/profiles/profile"));
+    }
+    let profiles = candidates.into_iter().filter_map(nix_profile).collect();
+    Some(NixInfo { profiles })
+}
+
+// Type 2 AppImages carry "AI\x02" at offset 8 of the ELF header, type 1 "AI\x01".
+fn appimage_type(path: &Path) -> Option<u8> {
+    let mut header = [0u8; 11];
+    fs::File::open(path).ok()?.read_exact(&mut header).ok()?;
+    (header.starts_with(b"\x7fELF") && &header[8..10] == b"AI").then_some(header[10])
+}
+
+fn appimages() -> Vec<AppImageInfo> {
+    let mut dirs: Vec<PathBuf> = vec![PathBuf::from("/opt")];
+    if let Some(home) = home_dir() {
+        dirs.extend(APPIMAGE_DIRS.iter().map(|dir| home.join(dir)));
+    }
+    dirs.iter()
+        .flat_map(list_dir_sorted)
+        .filter(|path| path.is_file())
+        .filter_map(|path| {
+            let named = file_name_string(&path)
+                .to_ascii_lowercase()
+                .ends_with(".appimage");
+            let appimage_type = appimage_type(&path);
+            if !named && appimage_type.is_none() {
+                return None;
+            }
+            let meta = fs::metadata(&path).ok()?;
+            Some(AppImageInfo {
+                path: path_string(&path),
+                size: meta.len(),
+                appimage_type,
+                executable: meta.permissions().mode() & 0o111 != 0,
+            })
+        })
+        .collect()
+}
+
+pub fn app_sources_info() -> AppSourcesInfo {
+    AppSourcesInfo {
+        flatpak: flatpak_info(),
+        snap: snap_info(),
+        nix: nix_info(),
+        appimages: appimages(),
+    }
+}
@@ -0,0 +1,140 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::utils::read_file_optional;
+
+const BOOSTER_CONF: &str = "/etc/booster.yaml";
+
+#[derive(Serialize)]
+pub struct BoosterInfo {
+    config_file: String,
+    universal: Option<bool>,
+    modules: Vec<String>,
+    modules_force_load: Vec<String>,
+    compression: Option<String>,
+    extra_files: Vec<String>,
+    mount_timeout: Option<String>,
+    strip: Option<bool>,
+    vconsole: Option<bool>,
+    enable_lvm: Option<bool>,
+    enable_mdraid: Option<bool>,
+    enable_zfs: Option<bool>,
+    network: BTreeMap<String, String>,
+}
+
+impl BoosterInfo {
+    pub fn config_file(&self) -> &str {
+        &self.config_file
+    }
+
+    pub fn compression(&self) -> Option<&str> {
+        self.compression.as_deref()
+    }
+
+    pub fn included_modules(&self) -> Vec<String> {
+        self.modules
+            .iter()
+            .chain(self.modules_force_load.iter())
+            .filter(|module| !module.starts_with('-'))
+            .cloned()
+            .collect()
+    }
+
+    pub fn features(&self) -> Vec<String> {
+        [
+            ("lvm", self.enable_lvm),
+            ("mdraid", self.enable_mdraid),
+            ("zfs", self.enable_zfs),
+        ]
+        .into_iter()
+        .filter(|(_, enabled)| *enabled == Some(true))
+        .map(|(name, _)| name.to_string())
+        .collect()
+    }
+}
+
+fn unquote_yaml(value: &str) -> String {
+    let value = match value.find(" #") {
+        Some(pos) => &value[..pos],
+        None => value,
+    };
+    value
+        .trim()
+        .trim_matches(|c| c == '"' || c == '\'')
+        .to_string()
+}
+
+// booster.yaml is a flat mapping with a single nested `network` block, so a
+// line-based reader that flattens nested keys to `parent.child` is enough.
+fn parse_booster_yaml(text: &str) -> BTreeMap<String, String> {
+    let mut values = BTreeMap::new();
+    let mut parent: Option<String> = None;
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') || trimmed == "---" {
+            continue;
+        }
+        let Some((key, value)) = trimmed.split_once(':') else {
+            continue;
+        };
+        let key = key.trim().to_string();
+        let value = unquote_yaml(value);
+        let indented = line.starts_with(' ') || line.starts_with('\t');
+        if !indented {
+            parent = value.is_empty().then(|| key.clone());
+            values.insert(key, value);
+        } else if let Some(parent) = &parent {
+            values.insert(format!("{parent}.{key}"), value);
+        }
+    }
+    values
+}
+
+fn parse_yaml_bool(value: &str) -> Option<bool> {
+    match value.to_ascii_lowercase().as_str() {
+        "true" | "yes" | "on" | "1" => Some(true),
+        "false" | "no" | "off" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+fn parse_yaml_list(value: &str) -> Vec<String> {
+    value
+        .trim_matches(|c| c == '[' || c == ']')
+        .split(',')
+        .map(|item| item.trim().trim_matches(|c| c == '"' || c == '\''))
+        .filter(|item| !item.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+pub fn booster_info() -> Option<BoosterInfo> {
+    let text = read_file_optional(BOOSTER_CONF)?;
+    let values = parse_booster_yaml(&text);
+    let get = |key: &str| values.get(key).filter(|v| !v.is_empty()).cloned();
+    let get_bool = |key: &str| get(key).as_deref().and_then(parse_yaml_bool);
+    let get_list = |key: &str| get(key).as_deref().map(parse_yaml_list).unwrap_or_default();
+    let network = values
+        .iter()
+        .filter_map(|(key, value)| {
+            key.strip_prefix("network.")
+                .map(|name| (name.to_string(), value.clone()))
+        })
+        .collect();
+    Some(BoosterInfo {
+        config_file: BOOSTER_CONF.to_string(),
+        universal: get_bool("universal"),
+        modules: get_list("modules"),
+        modules_force_load: get_list("modules_force_load"),
+        compression: get("compression"),
+        extra_files: get_list("extra_files"),
+        mount_timeout: get("mount_timeout"),
+        strip: get_bool("strip"),
+        vconsole: get_bool("vconsole"),
+        enable_lvm: get_bool("enable_lvm"),
+        enable_mdraid: get_bool("enable_mdraid"),
+        enable_zfs: get_bool("enable_zfs"),
+        network,
+    })
+}
@@ -0,0 +1,731 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::proc_info::ProcInfo;
+use crate::shell_config::ShellVars;
+use crate::utils::{list_dir_sorted, path_string, read_file_optional};
+
+const ESP_CANDIDATES: [&str; 3] = ["/efi", "/boot/efi", "/boot"];
+const GRUB_CONFIGS: [&str; 2] = ["/boot/grub/grub.cfg", "/boot/grub2/grub.cfg"];
+
+#[derive(Serialize)]
+pub struct BootInfo {
+    esp: Option<String>,
+    loaders: Vec<BootLoaderInfo>,
+}
+
+#[derive(Serialize)]
+pub struct BootLoaderInfo {
+    name: String,
+    config: Option<String>,
+    default_entry: Option<String>,
+    timeout: Option<String>,
+    entries: Vec<BootEntryInfo>,
+}
+
+#[derive(Serialize)]
+pub struct BootEntryInfo {
+    id: String,
+    title: Option<String>,
+    version: Option<String>,
+    kernel: Option<String>,
+    initrd: Vec<String>,
+    options: Option<String>,
+    is_default: bool,
+    cmdline_matches: Option<bool>,
+    cmdline_missing: Vec<String>,
+    cmdline_extra: Vec<String>,
+}
+
+impl BootEntryInfo {
+    fn new(id: String) -> Self {
+        BootEntryInfo {
+            id,
+            title: None,
+            version: None,
+            kernel: None,
+            initrd: Vec::new(),
+            options: None,
+            is_default: false,
+            cmdline_matches: None,
+            cmdline_missing: Vec::new(),
+            cmdline_extra: Vec::new(),
+        }
+    }
+}
+
+fn cmdline_tokens(value: &str) -> BTreeSet<String> {
+    // Loaders append their own arguments (`initrd=` for the EFI stub,
+    // `BOOT_IMAGE=` for GRUB) that never appear in the entry itself.
+    value
+        .split_whitespace()
+        .filter(|token| !token.starts_with("initrd=") && !token.starts_with("BOOT_IMAGE="))
+        .map(String::from)
+        .collect()
+}
+
+fn compare_with_cmdline(entry: &mut BootEntryInfo, running: &BTreeSet<String>) {
+    let Some(options) = &entry.options else {
+        return;
+    };
+    if running.is_empty() {
+        return;
+    }
+    let configured = cmdline_tokens(options);
+    entry.cmdline_missing = configured.difference(running).cloned().collect();
+    entry.cmdline_extra = running.difference(&configured).cloned().collect();
+    entry.cmdline_matches =
+        Some(entry.cmdline_missing.is_empty() && entry.cmdline_extra.is_empty());
+}
+
+fn glob_match(pattern: &str, value: &str) -> bool {
+    fn matches(pattern: &[char], value: &[char]) -> bool {
+        match pattern.split_first() {
+            None => value.is_empty(),
+            Some(('*', rest)) => (0..=value.len()).any(|skip| matches(rest, &value[skip..])),
+            Some(('?', rest)) => !value.is_empty() && matches(rest, &value[1..]),
+            Some((c, rest)) => value.first() == Some(c) && matches(rest, &value[1..]),
+        }
+    }
+    let pattern: Vec<char> = pattern.chars().collect();
+    let value: Vec<char> = value.chars().collect();
+    matches(&pattern, &value)
+}
+
+fn unquote(value: &str) -> String {
+    let value = value.trim();
+    let quoted = value.len() >= 2
+        && ((value.starts_with('"') && value.ends_with('"'))
+            || (value.starts_with('\'') && value.ends_with('\'')));
+    if quoted {
+        value[1..value.len() - 1].to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+fn split_key_value(line: &str) -> Option<(&str, &str)> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+    match line.split_once(char::is_whitespace) {
+        Some((key, value)) => Some((key, value.trim())),
+        None => Some((line, "")),
+    }
+}
+
+fn find_esp() -> Option<PathBuf> {
+    ESP_CANDIDATES
+        .iter()
+        .map(PathBuf::from)
+        .find(|candidate| candidate.join("EFI").is_dir())
+}
+
+fn parse_loader_entry(id: String, text: &str) -> BootEntryInfo {
+    let mut entry = BootEntryInfo::new(id);
+    let mut options = Vec::new();
+    for (key, value) in text.lines().filter_map(split_key_value) {
+        match key {
+            "title" => entry.title = Some(value.to_string()),
+            "version" => entry.version = Some(value.to_string()),
+            "linux" | "efi" | "uki" => entry.kernel = Some(value.to_string()),
+            "initrd" => entry
+                .initrd
+                .extend(value.split_whitespace().map(String::from)),
+            "options" => options.push(value.to_string()),
+            _ => {}
+        }
+    }
+    if !options.is_empty() {
+        entry.options = Some(options.join(" "));
+    }
+    entry
+}
+
+// loader.conf may name the entry with or without its ".conf" suffix.
+fn loader_entry_matches(pattern: &str, id: &str) -> bool {
+    let stem = id.strip_suffix(".conf").unwrap_or(id);
+    glob_match(pattern, id) || glob_match(pattern, stem)
+}
+
+fn systemd_boot_info(esp: Option<&Path>) -> Option<BootLoaderInfo> {
+    let esp = esp?;
+    let binary = esp.join("EFI/systemd").is_dir();
+    let loader_conf = esp.join("loader/loader.conf");
+    let config = read_file_optional(&loader_conf);
+    if !binary && config.is_none() {
+        return None;
+    }
+    let mut default_entry = None;
+    let mut timeout = None;
+    for (key, value) in config
+        .as_deref()
+        .unwrap_or_default()
+        .lines()
+        .filter_map(split_key_value)
+    {
+        match key {
+            "default" => default_entry = Some(value.to_string()),
+            "timeout" => timeout = Some(value.to_string()),
+            _ => {}
+        }
+    }
+
+    // Type #1 entries may live on the ESP or on an XBOOTLDR partition at /boot.
+    let mut entry_dirs: BTreeSet<PathBuf> = BTreeSet::new();
+    entry_dirs.insert(esp.join("loader/entries"));
+    entry_dirs.insert(PathBuf::from("/boot/loader/entries"));
+    let mut entries = Vec::new();
+    for dir in entry_dirs {
+        for path in list_dir_sorted(&dir) {
+            if path.extension().is_none_or(|ext| ext != "conf") {
+                continue;
+            }
+            let (Some(name), Some(text)) = (path.file_name(), read_file_optional(&path)) else {
+                continue;
+            };
+            let id = name.to_string_lossy().into_owned();
+            let mut entry = parse_loader_entry(id, &text);
+            entry.is_default = default_entry
+                .as_deref()
+                .is_some_and(|pattern| loader_entry_matches(pattern, &entry.id));
+            entries.push(entry);
+        }
+    }
+
+    Some(BootLoaderInfo {
+        name: "systemd-boot".to_string(),
+        config: config.map(|_| path_string(&loader_conf)),
+        default_entry,
+        timeout,
+        entries,
+    })
+}
+
+fn pe_section(data: &[u8], wanted: &str) -> Option<Vec<u8>> {
+    let read_u16 = |offset: usize| -> Option<usize> {
+        let bytes = data.get(offset..offset + 2)?;
+        Some(u16::from_le_bytes([bytes[0], bytes[1]]) as usize)
+    };
+    let read_u32 = |offset: usize| -> Option<usize> {
+        let bytes = data.get(offset..offset + 4)?;
+        Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize)
+    };
+    let pe_offset = read_u32(0x3c)?;
+    if data.get(pe_offset..pe_offset + 4)? != b"PE\0\0" {
+        return None;
+    }
+    let coff = pe_offset + 4;
+    let section_count = read_u16(coff + 2)?;
+    let optional_header_size = read_u16(coff + 16)?;
+    let sections = coff + 20 + optional_header_size;
+    for index in 0..section_count {
+        let header = sections + index * 40;
+        let name = data.get(header..header + 8)?;
+        let name = String::from_utf8_lossy(name);
+        if name.trim_end_matches('\0') != wanted {
+            continue;
+        }
+        let virtual_size = read_u32(header + 8)?;
+        let raw_size = read_u32(header + 16)?;
+        let raw_offset = read_u32(header + 20)?;
+        let size = virtual_size.min(raw_size);
+        return data.get(raw_offset..raw_offset + size).map(<[u8]>::to_vec);
+    }
+    None
+}
+
+fn pe_section_text(data: &[u8], name: &str) -> Option<String> {
+    let bytes = pe_section(data, name)?;
+    let text = String::from_utf8_lossy(&bytes)
+        .trim_end_matches('\0')
+        .trim()
+        .to_string();
+    if text.is_empty() {
+        None
+    } else {
+        Some(text)
+    }
+}
+
+fn uki_entry(path: &Path) -> Option<BootEntryInfo> {
+    let data = fs::read(path).ok()?;
+    let mut entry = BootEntryInfo::new(path.file_name()?.to_string_lossy().into_owned());
+    entry.kernel = Some(path_string(path));
+    entry.options = pe_section_text(&data, ".cmdline");
+    entry.version = pe_section_text(&data, ".uname");
+    if let Some(osrel) = pe_section_text(&data, ".osrel") {
+        let mut vars = ShellVars::default();
+        vars.parse(&osrel);
+        entry.title = vars
+            .non_empty_scalar("PRETTY_NAME")
+            .or_else(|| vars.non_empty_scalar("NAME"));
+    }
+    Some(entry)
+}
+
+fn uki_info(esp: Option<&Path>) -> Option<BootLoaderInfo> {
+    let mut dirs: BTreeSet<PathBuf> = BTreeSet::new();
+    if let Some(esp) = esp {
+        dirs.insert(esp.join("EFI/Linux"));
+    }
+    dirs.insert(PathBuf::from("/boot/EFI/Linux"));
+    let entries: Vec<BootEntryInfo> = dirs
+        .iter()
+        .flat_map(list_dir_sorted)
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("efi"))
+        })
+        .filter_map(|path| uki_entry(&path))
+        .collect();
+    if entries.is_empty() {
+        return None;
+    }
+    Some(BootLoaderInfo {
+        name: "uki".to_string(),
+        config: None,
+        default_entry: None,
+        timeout: None,
+        entries,
+    })
+}
+
+fn grub_menu_title(line: &str) -> (Option<String>, Option<String>) {
+    let mut quoted = Vec::new();
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\'' || c == '"' {
+            let mut value = String::new();
+            for inner in chars.by_ref() {
+                if inner == c {
+                    break;
+                }
+                value.push(inner);
+            }
+            quoted.push(value);
+        }
+    }
+    let id = line
+        .find("$menuentry_id_option")
+        .and_then(|_| quoted.get(1).cloned());
+    (quoted.into_iter().next(), id)
+}
+
+// A parsed GRUB menu entry together with its `$menuentry_id_option` id.
+type GrubEntry = (BootEntryInfo, Option<String>);
+
+fn parse_grub_cfg(text: &str) -> (Option<String>, Option<String>, Vec<GrubEntry>) {
+    let mut default_entry = None;
+    let mut timeout = None;
+    let mut entries = Vec::new();
+    let mut path: Vec<usize> = Vec::new();
+    let mut counters: Vec<usize> = vec![0];
+    let mut titles: Vec<String> = Vec::new();
+    let mut current: Option<GrubEntry> = None;
+    for line in text.lines() {
+        let line = line.trim();
+        if line.starts_with('#') || line.is_empty() {
+            continue;
+        }
+        // grub-mkconfig first sets default="${next_entry}" for one-shot boots; the
+        // real default follows in the else branch. "${saved_entry}" is kept so that
+        // grub_info() can resolve it from grubenv.
+        if let Some(rest) = line.strip_prefix("set default=") {
+            let value = unquote(rest);
+            if default_entry.is_none() && (!value.contains('$') || value.contains("saved_entry")) {
+                default_entry = Some(value);
+            }
+            continue;
+        }
+        if let Some(rest) = line.strip_prefix("set timeout=") {
+            timeout = Some(unquote(rest));
+            continue;
+        }
+        let is_menuentry = line.starts_with("menuentry ");
+        let is_submenu = line.starts_with("submenu ");
+        if is_menuentry || is_submenu {
+            let depth = counters.len() - 1;
+            let index = counters[depth];
+            counters[depth] += 1;
+            let (title, menu_id) = grub_menu_title(line);
+            path.push(index);
+            titles.push(title.clone().unwrap_or_default());
+            if is_submenu {
+                counters.push(0);
+            } else {
+                let id = path
+                    .iter()
+                    .map(usize::to_string)
+                    .collect::<Vec<_>>()
+                    .join(">");
+                let mut entry = BootEntryInfo::new(id);
+                entry.title = Some(titles.join(">"));
+                current = Some((entry, menu_id));
+            }
+            continue;
+        }
+        if line == "}" {
+            if let Some(entry) = current.take() {
+                entries.push(entry);
+            } else if counters.len() > 1 {
+                counters.pop();
+            } else {
+                continue;
+            }
+            path.pop();
+            titles.pop();
+            continue;
+        }
+        let Some((entry, _)) = current.as_mut() else {
+            continue;
+        };
+        let Some((key, value)) = split_key_value(line) else {
+            continue;
+        };
+        match key {
+            "linux" | "linuxefi" | "linux16" => {
+                let mut parts = value.splitn(2, char::is_whitespace);
+                entry.kernel = parts.next().map(String::from);
+                entry.options = parts
+                    .next()
+                    .map(|options| options.trim().to_string())
+                    .filter(|options| !options.is_empty());
+            }
+            "initrd" | "initrdefi" | "initrd16" => {
+                entry
+                    .initrd
+                    .extend(value.split_whitespace().map(String::from));
+            }
+            _ => {}
+        }
+    }
+    (default_entry, timeout, entries)
+}
+
+fn grub_saved_entry(config: &Path) -> Option<String> {
+    let env = read_file_optional(config.with_file_name("grubenv"))?;
+    env.lines()
+        .find_map(|line| line.strip_prefix("saved_entry="))
+        .map(String::from)
+        .filter(|value| !value.is_empty())
+}
+
+// "saved" (or grub.cfg's "${saved_entry}") defers to grubenv, falling back to
+// the first entry when nothing has been saved yet.
+fn resolve_grub_default(
+    default_entry: Option<String>,
+    saved_entry: impl FnOnce() -> Option<String>,
+) -> Option<String> {
+    match default_entry {
+        Some(value) if value == "saved" || value.contains("saved_entry") => {
+            saved_entry().or(Some("0".to_string()))
+        }
+        other => other,
+    }
+}
+
+// GRUB_DEFAULT may be a numeric path ("1>0"), a title or a menuentry id.
+fn mark_grub_default(parsed: Vec<GrubEntry>, default_entry: Option<&str>) -> Vec<BootEntryInfo> {
+    parsed
+        .into_iter()
+        .map(|(mut entry, menu_id)| {
+            entry.is_default = default_entry.is_some_and(|value| {
+                value == entry.id
+                    || entry.title.as_deref() == Some(value)
+                    || menu_id.as_deref() == Some(value)
+            });
+            entry
+        })
+        .collect()
+}
+
+fn grub_info() -> Option<BootLoaderInfo> {
+    let config = GRUB_CONFIGS
+        .iter()
+        .map(Path::new)
+        .find(|path| path.is_file())?;
+    let text = read_file_optional(config).unwrap_or_default();
+    let (cfg_default, cfg_timeout, parsed) = parse_grub_cfg(&text);
+
+    let mut defaults = ShellVars::default();
+    if let Some(text) = read_file_optional("/etc/default/grub") {
+        defaults.parse(&text);
+    }
+    let default_entry = resolve_grub_default(
+        defaults.non_empty_scalar("GRUB_DEFAULT").or(cfg_default),
+        || grub_saved_entry(config),
+    );
+    let timeout = defaults.non_empty_scalar("GRUB_TIMEOUT").or(cfg_timeout);
+    let entries = mark_grub_default(parsed, default_entry.as_deref());
+
+    Some(BootLoaderInfo {
+        name: "grub".to_string(),
+        config: Some(path_string(config)),
+        default_entry,
+        timeout,
+        entries,
+    })
+}
+
+fn parse_refind_conf(text: &str) -> (Option<String>, Option<String>, Vec<BootEntryInfo>) {
+    let mut default_entry = None;
+    let mut timeout = None;
+    let mut entries = Vec::new();
+    let mut current: Option<BootEntryInfo> = None;
+    let mut depth = 0usize;
+    for line in text.lines() {
+        let Some((key, value)) = split_key_value(line) else {
+            continue;
+        };
+        let Some(entry) = current.as_mut() else {
+            match key {
+                "timeout" => timeout = Some(value.to_string()),
+                "default_selection" => default_entry = Some(unquote(value)),
+                "menuentry" => {
+                    let title = unquote(value.trim_end_matches('{'));
+                    let mut entry = BootEntryInfo::new(title.clone());
+                    entry.title = Some(title);
+                    current = Some(entry);
+                    depth = 1;
+                }
+                _ => {}
+            }
+            continue;
+        };
+        match key {
+            "}" => {
+                depth -= 1;
+                if depth == 0 {
+                    entries.extend(current.take());
+                }
+            }
+            "submenuentry" => depth += 1,
+            "loader" if depth == 1 => entry.kernel = Some(unquote(value)),
+            "initrd" if depth == 1 => entry.initrd.push(unquote(value)),
+            "options" if depth == 1 => entry.options = Some(unquote(value)),
+            _ => {}
+        }
+    }
+    (default_entry, timeout, entries)
+}
+
+fn parse_refind_linux_conf(text: &str) -> Vec<BootEntryInfo> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split('"').collect();
+            let title = fields.get(1)?.to_string();
+            let mut entry = BootEntryInfo::new(title.clone());
+            entry.title = Some(title);
+            entry.options = fields.get(3).map(|value| value.to_string());
+            Some(entry)
+        })
+        .collect()
+}
+
+fn refind_info(esp: Option<&Path>) -> Option<BootLoaderInfo> {
+    let config = esp?.join("EFI/refind/refind.conf");
+    let text = read_file_optional(&config)?;
+    let (default_entry, timeout, mut entries) = parse_refind_conf(&text);
+    if let Some(text) = read_file_optional("/boot/refind_linux.conf") {
+        entries.extend(parse_refind_linux_conf(&text));
+    }
+    for entry in &mut entries {
+        entry.is_default = default_entry.as_deref().is_some_and(|value| {
+            entry
+                .title
+                .as_deref()
+                .is_some_and(|title| title.contains(value))
+        });
+    }
+    Some(BootLoaderInfo {
+        name: "refind".to_string(),
+        config: Some(path_string(&config)),
+        default_entry,
+        timeout,
+        entries,
+    })
+}
+
+pub fn boot_info(proc: &ProcInfo) -> BootInfo {
+    let esp = find_esp();
+    let running = cmdline_tokens(proc.cmdline());
+    let mut loaders: Vec<BootLoaderInfo> = [
+        systemd_boot_info(esp.as_deref()),
+        grub_info(),
+        refind_info(esp.as_deref()),
+        uki_info(esp.as_deref()),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    for loader in &mut loaders {
+        for entry in &mut loader.entries {
+            compare_with_cmdline(entry, &running);
+        }
+    }
+    BootInfo {
+        esp: esp.map(path_string),
+        loaders,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GRUB_CFG_FIXTURE: &str = "\
+if [ \"${next_entry}\" ] ; then
+   set default=\"${next_entry}\"
+   set next_entry=
+   save_env next_entry
+   set boot_once=true
+else
+   set default=\"${saved_entry}\"
+fi
+set timeout=5
+menuentry 'Arch Linux' --class arch $menuentry_id_option 'gnulinux-simple-1234' {
+	linux	/vmlinuz-linux root=UUID=1234 rw quiet
+	initrd	/intel-ucode.img /initramfs-linux.img
+}
+submenu 'Advanced options for Arch Linux' $menuentry_id_option 'gnulinux-advanced-1234' {
+	menuentry 'Arch Linux, with Linux linux' $menuentry_id_option 'gnulinux-linux-advanced-1234' {
+		linux	/vmlinuz-linux root=UUID=1234 rw quiet
+	}
+	menuentry 'Arch Linux, with Linux linux (fallback initramfs)' $menuentry_id_option 'gnulinux-linux-fallback-1234' {
+		linux	/vmlinuz-linux root=UUID=1234 rw
+		initrd	/initramfs-linux-fallback.img
+	}
+}
+menuentry 'UEFI Firmware Settings' $menuentry_id_option 'uefi-firmware' {
+	fwsetup
+}
+";
+
+    #[test]
+    fn parses_grub_cfg_entries_and_submenus() {
+        let (default_entry, timeout, parsed) = parse_grub_cfg(GRUB_CFG_FIXTURE);
+        assert_eq!(default_entry.as_deref(), Some("${saved_entry}"));
+        assert_eq!(timeout.as_deref(), Some("5"));
+        let ids: Vec<&str> = parsed.iter().map(|(entry, _)| entry.id.as_str()).collect();
+        assert_eq!(ids, ["0", "1>0", "1>1", "2"]);
+        let (fallback, menu_id) = &parsed[2];
+        assert_eq!(
+            fallback.title.as_deref(),
+            Some(
+                "Advanced options for Arch Linux>Arch Linux, with Linux linux (fallback initramfs)"
+            )
+        );
+        assert_eq!(menu_id.as_deref(), Some("gnulinux-linux-fallback-1234"));
+        assert_eq!(fallback.kernel.as_deref(), Some("/vmlinuz-linux"));
+        assert_eq!(fallback.options.as_deref(), Some("root=UUID=1234 rw"));
+        assert_eq!(fallback.initrd, ["/initramfs-linux-fallback.img"]);
+        assert_eq!(parsed[0].0.initrd.len(), 2);
+    }
+
+    #[test]
+    fn resolves_grub_saved_default() {
+        let default_entry = resolve_grub_default(Some("${saved_entry}".to_string()), || {
+            Some("1>1".to_string())
+        });
+        assert_eq!(default_entry.as_deref(), Some("1>1"));
+        let (_, _, parsed) = parse_grub_cfg(GRUB_CFG_FIXTURE);
+        let defaults: Vec<bool> = mark_grub_default(parsed, default_entry.as_deref())
+            .iter()
+            .map(|entry| entry.is_default)
+            .collect();
+        assert_eq!(defaults, [false, false, true, false]);
+
+        // Nothing saved yet: GRUB boots the first entry.
+        assert_eq!(
+            resolve_grub_default(Some("saved".to_string()), || None).as_deref(),
+            Some("0")
+        );
+        // A menuentry id selects the entry carrying it.
+        let (_, _, parsed) = parse_grub_cfg(GRUB_CFG_FIXTURE);
+        let marked = mark_grub_default(parsed, Some("gnulinux-linux-advanced-1234"));
+        assert!(marked[1].is_default);
+    }
+
+    #[test]
+    fn matches_systemd_boot_default_patterns() {
+        assert!(loader_entry_matches("arch.conf", "arch.conf"));
+        assert!(loader_entry_matches("arch", "arch.conf"));
+        assert!(loader_entry_matches("arch-*", "arch-6.9.1.conf"));
+        assert!(!loader_entry_matches("arch", "arch-lts.conf"));
+        assert!(glob_match("a?c*", "abcdef"));
+        assert!(!glob_match("a?c", "ac"));
+    }
+
+    #[test]
+    fn parses_refind_conf() {
+        let text = "\
+timeout 10
+default_selection \"Arch\"
+menuentry \"Arch Linux\" {
+    loader /vmlinuz-linux
+    initrd /initramfs-linux.img
+    options \"root=PARTUUID=abcd rw\"
+    submenuentry \"Fallback\" {
+        initrd /initramfs-linux-fallback.img
+    }
+}
+";
+        let (default_entry, timeout, entries) = parse_refind_conf(text);
+        assert_eq!(default_entry.as_deref(), Some("Arch"));
+        assert_eq!(timeout.as_deref(), Some("10"));
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].kernel.as_deref(), Some("/vmlinuz-linux"));
+        assert_eq!(entries[0].initrd, ["/initramfs-linux.img"]);
+        assert_eq!(entries[0].options.as_deref(), Some("root=PARTUUID=abcd rw"));
+    }
+
+    // A PE image reduced to what pe_section reads: the DOS e_lfanew pointer, the
+    // COFF header and two section headers.
+    fn pe_fixture() -> Vec<u8> {
+        let sections: [(&str, &[u8]); 2] = [
+            (
+                ".osrel",
+                b"NAME=\"Arch Linux\"\nPRETTY_NAME=\"Arch Linux\"\n",
+            ),
+            (".cmdline", b"root=UUID=1234 rw quiet\0\0"),
+        ];
+        let mut data = vec![0u8; 0x200];
+        data[0..2].copy_from_slice(b"MZ");
+        data[0x3c..0x40].copy_from_slice(&0x80u32.to_le_bytes());
+        data[0x80..0x84].copy_from_slice(b"PE\0\0");
+        let coff = 0x84;
+        data[coff + 2..coff + 4].copy_from_slice(&(sections.len() as u16).to_le_bytes());
+        for (index, (name, content)) in sections.iter().enumerate() {
+            let header = coff + 20 + index * 40;
+            let raw_offset = data.len();
+            data[header..header + name.len()].copy_from_slice(name.as_bytes());
+            data[header + 8..header + 12].copy_from_slice(&(content.len() as u32).to_le_bytes());
+            // Raw data is padded to the file alignment.
+            data[header + 16..header + 20].copy_from_slice(&0x200u32.to_le_bytes());
+            data[header + 20..header + 24].copy_from_slice(&(raw_offset as u32).to_le_bytes());
+            data.extend_from_slice(content);
+            data.resize(raw_offset + 0x200, 0);
+        }
+        data
+    }
+
+    #[test]
+    fn reads_pe_sections() {
+        let data = pe_fixture();
+        assert_eq!(
+            pe_section_text(&data, ".cmdline").as_deref(),
+            Some("root=UUID=1234 rw quiet")
+        );
+        let osrel = pe_section_text(&data, ".osrel").unwrap();
+        assert!(osrel.starts_with("NAME=\"Arch Linux\""));
+        assert_eq!(pe_section(&data, ".uname"), None);
+        assert_eq!(pe_section(&data[..0x40], ".osrel"), None);
+    }
+}
@@ -0,0 +1,260 @@
+use std::fs::File;
+use std::os::fd::AsRawFd;
+use std::os::raw::{c_int, c_ulong};
+use std::path::Path;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::lsblk_info::BlockDeviceInfo;
+use crate::utils::{
+    find_in_path, json_str, json_u64, read_file_optional, redact_serial, run_command_string,
+};
+
+// _IOWR('N', 0x41, struct nvme_admin_cmd) from <linux/nvme_ioctl.h>.
+const NVME_IOCTL_ADMIN_CMD: c_ulong = 0xc048_4e41;
+const NVME_ADMIN_GET_LOG_PAGE: u8 = 0x02;
+const NVME_LOG_SMART: u32 = 0x02;
+const NVME_SMART_LOG_LEN: usize = 512;
+
+unsafe extern "C" {
+    fn ioctl(fd: c_int, request: c_ulong, ...) -> c_int;
+}
+
+// struct nvme_passthru_cmd (a.k.a. nvme_admin_cmd).
+#[repr(C)]
+#[derive(Default)]
+struct NvmePassthruCmd {
+    opcode: u8,
+    flags: u8,
+    rsvd1: u16,
+    nsid: u32,
+    cdw2: u32,
+    cdw3: u32,
+    metadata: u64,
+    addr: u64,
+    metadata_len: u32,
+    data_len: u32,
+    cdw10: u32,
+    cdw11: u32,
+    cdw12: u32,
+    cdw13: u32,
+    cdw14: u32,
+    cdw15: u32,
+    timeout_ms: u32,
+    result: u32,
+}
+
+const _: () = assert!(std::mem::size_of::<NvmePassthruCmd>() == 72);
+
+#[derive(Serialize)]
+pub struct DiskHealthInfo {
+    path: String,
+    source: String,
+    model: Option<String>,
+    serial: Option<String>,
+    firmware: Option<String>,
+    power_on_hours: Option<u64>,
+    temperature_celsius: Option<i64>,
+    reallocated_sectors: Option<u64>,
+    pending_sectors: Option<u64>,
+    percentage_used: Option<u64>,
+    media_errors: Option<u64>,
+    health_passed: Option<bool>,
+}
+
+impl DiskHealthInfo {
+    fn new(path: &str, source: &str) -> Self {
+        DiskHealthInfo {
+            path: path.to_string(),
+            source: source.to_string(),
+            model: None,
+            serial: None,
+            firmware: None,
+            power_on_hours: None,
+            temperature_celsius: None,
+            reallocated_sectors: None,
+            pending_sectors: None,
+            percentage_used: None,
+            media_errors: None,
+            health_passed: None,
+        }
+    }
+}
+
+fn ata_attribute_raw(report: &Value, id: u64) -> Option<u64> {
+    report
+        .pointer("/ata_smart_attributes/table")?
+        .as_array()?
+        .iter()
+        .find(|attr| attr.get("id").and_then(Value::as_u64) == Some(id))?
+        .pointer("/raw/value")?
+        .as_u64()
+}
+
+fn parse_smartctl(path: &str, report: &Value) -> DiskHealthInfo {
+    let nvme = |key: &str| json_u64(report, &format!("/nvme_smart_health_information_log/{key}"));
+    let mut info = DiskHealthInfo::new(path, "smartctl");
+    info.model = json_str(report, "/model_name");
+    info.serial = json_str(report, "/serial_number");
+    info.firmware = json_str(report, "/firmware_version");
+    info.power_on_hours =
+        json_u64(report, "/power_on_time/hours").or_else(|| nvme("power_on_hours"));
+    info.temperature_celsius = report
+        .pointer("/temperature/current")
+        .and_then(Value::as_i64);
+    info.reallocated_sectors = ata_attribute_raw(report, 5);
+    info.pending_sectors = ata_attribute_raw(report, 197);
+    info.percentage_used = nvme("percentage_used");
+    info.media_errors = nvme("media_errors");
+    info.health_passed = report
+        .pointer("/smart_status/passed")
+        .and_then(Value::as_bool);
+    info
+}
+
+// smartctl prints a JSON report even when it cannot open the device (e.g.
+// "Permission denied"); such a report has neither identity nor a verdict.
+fn parse_smartctl_output(path: &str, output: &str) -> Option<DiskHealthInfo> {
+    let report: Value = serde_json::from_str(output).ok()?;
+    if report.get("model_name").is_none() && report.get("smart_status").is_none() {
+        return None;
+    }
+    Some(parse_smartctl(path, &report))
+}
+
+fn smartctl_health(path: &str) -> Option<DiskHealthInfo> {
+    // smartctl encodes disk problems in its exit status, so the output is
+    // used regardless of whether the command "succeeded".
+    let output = run_command_string("smartctl", &["--json", "-a", path]);
+    parse_smartctl_output(path, &output)
+}
+
+// Get Log Page (SMART / Health Information) through the admin passthrough
+// ioctl; the kernel requires CAP_SYS_ADMIN for it.
+fn nvme_smart_log(path: &str) -> Option<[u8; NVME_SMART_LOG_LEN]> {
+    let file = File::open(path).ok()?;
+    let mut log = [0u8; NVME_SMART_LOG_LEN];
+    let dwords = (NVME_SMART_LOG_LEN / 4 - 1) as u32;
+    let mut cmd = NvmePassthruCmd {
+        opcode: NVME_ADMIN_GET_LOG_PAGE,
+        nsid: 0xffff_ffff,
+        addr: log.as_mut_ptr() as u64,
+        data_len: NVME_SMART_LOG_LEN as u32,
+        cdw10: (dwords << 16) | NVME_LOG_SMART,
+        ..Default::default()
+    };
+    // SAFETY: `cmd` matches struct nvme_passthru_cmd and `addr` points at a
+    // buffer of `data_len` bytes that outlives the call.
+    let ret = unsafe { ioctl(file.as_raw_fd(), NVME_IOCTL_ADMIN_CMD, &mut cmd) };
+    (ret == 0).then_some(log)
+}
+
+// Counters in the SMART log are 128-bit little-endian; the low 64 bits are plenty.
+fn apply_nvme_smart_log(info: &mut DiskHealthInfo, log: &[u8]) {
+    let le_u64 = |offset: usize| {
+        log.get(offset..offset + 8)
+            .and_then(|bytes| bytes.try_into().ok())
+            .map(u64::from_le_bytes)
+    };
+    let kelvin = u16::from_le_bytes([log[1], log[2]]);
+    info.health_passed = Some(log[0] == 0);
+    info.temperature_celsius = (kelvin > 0).then(|| kelvin as i64 - 273);
+    info.percentage_used = Some(log[5] as u64);
+    info.power_on_hours = le_u64(128);
+    info.media_errors = le_u64(160);
+}
+
+fn nvme_health(path: &str, sys_device: &Path) -> Option<DiskHealthInfo> {
+    let mut info = DiskHealthInfo::new(path, "nvme");
+    if let Some(log) = nvme_smart_log(path) {
+        apply_nvme_smart_log(&mut info, &log);
+    }
+    let controller = sys_device.join("device");
+    info.model = read_file_optional(controller.join("model"));
+    info.serial = read_file_optional(controller.join("serial"));
+    info.firmware = read_file_optional(controller.join("firmware_rev"));
+    info.model.as_ref()?;
+    Some(info)
+}
+
+fn sysfs_identity(path: &str, sys_device: &Path) -> Option<DiskHealthInfo> {
+    let device = sys_device.join("device");
+    let model = read_file_optional(device.join("model"))?;
+    let mut info = DiskHealthInfo::new(path, "sysfs");
+    info.model = Some(model);
+    info.serial = read_file_optional(device.join("serial"));
+    info.firmware = read_file_optional(device.join("rev"))
+        .or_else(|| read_file_optional(device.join("firmware_rev")));
+    Some(info)
+}
+
+pub fn disk_health_info(devices: &[BlockDeviceInfo], redact_serials: bool) -> Vec<DiskHealthInfo> {
+    let has_smartctl = find_in_path("smartctl").is_some();
+    devices
+        .iter()
+        .filter(|dev| dev.dev_type() == "disk")
+        .filter_map(|dev| {
+            let sys_device = Path::new("/sys/block").join(dev.name());
+            // Virtual disks (loop, zram, ram) have no backing device link.
+            if !sys_device.join("device").exists() {
+                return None;
+            }
+            let path = dev.path();
+            let mut info = has_smartctl
+                .then(|| smartctl_health(path))
+                .flatten()
+                .or_else(|| {
+                    dev.name()
+                        .starts_with("nvme")
+                        .then(|| nvme_health(path, &sys_device))
+                        .flatten()
+                })
+                .or_else(|| sysfs_identity(path, &sys_device))?;
+            if redact_serials {
+                info.serial = info.serial.as_deref().map(redact_serial);
+            }
+            Some(info)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_smartctl_reports_without_data() {
+        let denied = r#"{"smartctl":{"exit_status":2,"messages":[{"string":"Permission denied"}]},
+            "device":{"name":"/dev/sda","type":"scsi"}}"#;
+        assert!(parse_smartctl_output("/dev/sda", denied).is_none());
+
+        let report = r#"{"device":{"name":"/dev/sda"},"model_name":"WDC WD10EZEX",
+            "serial_number":"WD-1234","smart_status":{"passed":true},
+            "power_on_time":{"hours":1200},"temperature":{"current":31},
+            "ata_smart_attributes":{"table":[{"id":5,"raw":{"value":0}},{"id":197,"raw":{"value":3}}]}}"#;
+        let info = parse_smartctl_output("/dev/sda", report).unwrap();
+        assert_eq!(info.model.as_deref(), Some("WDC WD10EZEX"));
+        assert_eq!(info.power_on_hours, Some(1200));
+        assert_eq!(info.temperature_celsius, Some(31));
+        assert_eq!(info.reallocated_sectors, Some(0));
+        assert_eq!(info.pending_sectors, Some(3));
+        assert_eq!(info.health_passed, Some(true));
+    }
+
+    #[test]
+    fn decodes_nvme_smart_log() {
+        let mut log = [0u8; NVME_SMART_LOG_LEN];
+        log[1..3].copy_from_slice(&310u16.to_le_bytes());
+        log[5] = 7;
+        log[128..136].copy_from_slice(&4321u64.to_le_bytes());
+        log[160..168].copy_from_slice(&2u64.to_le_bytes());
+        let mut info = DiskHealthInfo::new("/dev/nvme0n1", "nvme");
+        apply_nvme_smart_log(&mut info, &log);
+        assert_eq!(info.health_passed, Some(true));
+        assert_eq!(info.temperature_celsius, Some(37));
+        assert_eq!(info.percentage_used, Some(7));
+        assert_eq!(info.power_on_hours, Some(4321));
+        assert_eq!(info.media_errors, Some(2));
+    }
+}
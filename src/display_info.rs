@@ -0,0 +1,373 @@
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::utils::{
+    env_var_opt, json_bool, json_f64, json_i64, json_str, json_u64, list_dir_sorted,
+    read_file_optional, run_command_optional,
+};
+use crate::xdg_info::XdgInfo;
+
+const COMPOSITOR_PROCESSES: [(&str, &str); 9] = [
+    ("sway", "sway"),
+    ("Hyprland", "hyprland"),
+    ("gnome-shell", "mutter"),
+    ("kwin_wayland", "kwin"),
+    ("river", "river"),
+    ("wayfire", "wayfire"),
+    ("labwc", "labwc"),
+    ("niri", "niri"),
+    ("weston", "weston"),
+];
+
+#[derive(Serialize)]
+pub struct DisplayInfo {
+    session_type: String,
+    wayland_display: Option<String>,
+    x_display: Option<String>,
+    xwayland: bool,
+    compositor: Option<String>,
+    outputs_source: Option<String>,
+    outputs: Vec<DisplayOutputInfo>,
+}
+
+#[derive(Serialize)]
+pub struct DisplayOutputInfo {
+    name: String,
+    description: Option<String>,
+    make: Option<String>,
+    model: Option<String>,
+    enabled: Option<bool>,
+    current_mode: Option<DisplayModeInfo>,
+    modes: Vec<DisplayModeInfo>,
+    scale: Option<f64>,
+    transform: Option<String>,
+    position_x: Option<i64>,
+    position_y: Option<i64>,
+}
+
+#[derive(Clone, Serialize)]
+pub struct DisplayModeInfo {
+    width: u64,
+    height: u64,
+    refresh_hz: Option<f64>,
+    preferred: bool,
+}
+
+impl DisplayOutputInfo {
+    fn new(name: String) -> Self {
+        DisplayOutputInfo {
+            name,
+            description: None,
+            make: None,
+            model: None,
+            enabled: None,
+            current_mode: None,
+            modes: Vec::new(),
+            scale: None,
+            transform: None,
+            position_x: None,
+            position_y: None,
+        }
+    }
+}
+
+fn round_refresh(hz: f64) -> f64 {
+    (hz * 1000.0).round() / 1000.0
+}
+
+// The wl_output transform enum shared by sway, Hyprland and wlr-randr.
+fn transform_name(value: i64) -> String {
+    match value {
+        0 => "normal",
+        1 => "90",
+        2 => "180",
+        3 => "270",
+        4 => "flipped",
+        5 => "flipped-90",
+        6 => "flipped-180",
+        7 => "flipped-270",
+        _ => return value.to_string(),
+    }
+    .to_string()
+}
+
+// "1920x1080@60.00Hz"
+fn parse_mode_string(value: &str) -> Option<DisplayModeInfo> {
+    let (size, refresh) = value.split_once('@').unwrap_or((value, ""));
+    let (width, height) = size.split_once('x')?;
+    Some(DisplayModeInfo {
+        width: width.trim().parse().ok()?,
+        height: height.trim().parse().ok()?,
+        refresh_hz: refresh.trim().trim_end_matches("Hz").parse().ok(),
+        preferred: false,
+    })
+}
+
+fn sway_mode(mode: &Value) -> Option<DisplayModeInfo> {
+    Some(DisplayModeInfo {
+        width: json_u64(mode, "/width")?,
+        height: json_u64(mode, "/height")?,
+        // sway reports refresh rates in mHz.
+        refresh_hz: json_f64(mode, "/refresh").map(|mhz| round_refresh(mhz / 1000.0)),
+        preferred: false,
+    })
+}
+
+fn sway_outputs(output: &str) -> Option<Vec<DisplayOutputInfo>> {
+    let parsed: Value = serde_json::from_str(output).ok()?;
+    let outputs = parsed
+        .as_array()?
+        .iter()
+        .map(|item| {
+            let mut info = DisplayOutputInfo::new(json_str(item, "/name").unwrap_or_default());
+            info.make = json_str(item, "/make");
+            info.model = json_str(item, "/model");
+            info.enabled = json_bool(item, "/active");
+            info.current_mode = item.get("current_mode").and_then(sway_mode);
+            info.modes = item
+                .get("modes")
+                .and_then(Value::as_array)
+                .map(|modes| modes.iter().filter_map(sway_mode).collect())
+                .unwrap_or_default();
+            info.scale = json_f64(item, "/scale");
+            info.transform = json_str(item, "/transform");
+            info.position_x = json_i64(item, "/rect/x");
+            info.position_y = json_i64(item, "/rect/y");
+            info
+        })
+        .collect();
+    Some(outputs)
+}
+
+fn hyprland_outputs(output: &str) -> Option<Vec<DisplayOutputInfo>> {
+    let parsed: Value = serde_json::from_str(output).ok()?;
+    let outputs = parsed
+        .as_array()?
+        .iter()
+        .map(|item| {
+            let mut info = DisplayOutputInfo::new(json_str(item, "/name").unwrap_or_default());
+            info.description = json_str(item, "/description");
+            info.make = json_str(item, "/make");
+            info.model = json_str(item, "/model");
+            info.enabled = json_bool(item, "/disabled").map(|disabled| !disabled);
+            info.current_mode = match (json_u64(item, "/width"), json_u64(item, "/height")) {
+                (Some(width), Some(height)) => Some(DisplayModeInfo {
+                    width,
+                    height,
+                    refresh_hz: json_f64(item, "/refreshRate").map(round_refresh),
+                    preferred: false,
+                }),
+                _ => None,
+            };
+            info.modes = item
+                .get("availableModes")
+                .and_then(Value::as_array)
+                .map(|modes| {
+                    modes
+                        .iter()
+                        .filter_map(Value::as_str)
+                        .filter_map(parse_mode_string)
+                        .collect()
+                })
+                .unwrap_or_default();
+            info.scale = json_f64(item, "/scale");
+            info.transform = json_i64(item, "/transform").map(transform_name);
+            info.position_x = json_i64(item, "/x");
+            info.position_y = json_i64(item, "/y");
+            info
+        })
+        .collect();
+    Some(outputs)
+}
+
+fn wlr_randr_outputs(output: &str) -> Option<Vec<DisplayOutputInfo>> {
+    let parsed: Value = serde_json::from_str(output).ok()?;
+    let outputs = parsed
+        .as_array()?
+        .iter()
+        .map(|item| {
+            let mut info = DisplayOutputInfo::new(json_str(item, "/name").unwrap_or_default());
+            info.description = json_str(item, "/description");
+            info.make = json_str(item, "/make");
+            info.model = json_str(item, "/model");
+            info.enabled = json_bool(item, "/enabled");
+            let modes: Vec<(DisplayModeInfo, bool)> = item
+                .get("modes")
+                .and_then(Value::as_array)
+                .map(|modes| {
+                    modes
+                        .iter()
+                        .filter_map(|mode| {
+                            let info = DisplayModeInfo {
+                                width: json_u64(mode, "/width")?,
+                                height: json_u64(mode, "/height")?,
+                                refresh_hz: json_f64(mode, "/refresh").map(round_refresh),
+                                preferred: json_bool(mode, "/preferred").unwrap_or(false),
+                            };
+                            Some((info, json_bool(mode, "/current").unwrap_or(false)))
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            info.current_mode = modes
+                .iter()
+                .find(|(_, current)| *current)
+                .map(|(mode, _)| mode.clone());
+            info.modes = modes.into_iter().map(|(mode, _)| mode).collect();
+            info.scale = json_f64(item, "/scale");
+            info.transform = json_str(item, "/transform");
+            info.position_x = json_i64(item, "/position/x");
+            info.position_y = json_i64(item, "/position/y");
+            info
+        })
+        .collect();
+    Some(outputs)
+}
+
+fn kscreen_outputs(output: &str) -> Option<Vec<DisplayOutputInfo>> {
+    let parsed: Value = serde_json::from_str(output).ok()?;
+    let outputs = parsed
+        .get("outputs")?
+        .as_array()?
+        .iter()
+        .filter(|item| json_bool(item, "/connected").unwrap_or(true))
+        .map(|item| {
+            let mut info = DisplayOutputInfo::new(json_str(item, "/name").unwrap_or_default());
+            info.enabled = json_bool(item, "/enabled");
+            let current_id = item.get("currentModeId").and_then(Value::as_str);
+            let preferred: Vec<&str> = item
+                .get("preferredModes")
+                .and_then(Value::as_array)
+                .map(|ids| ids.iter().filter_map(Value::as_str).collect())
+                .unwrap_or_default();
+            let modes = item.get("modes").and_then(Value::as_array);
+            for mode in modes.into_iter().flatten() {
+                let id = mode.get("id").and_then(Value::as_str);
+                let (Some(width), Some(height)) = (
+                    json_u64(mode, "/size/width"),
+                    json_u64(mode, "/size/height"),
+                ) else {
+                    continue;
+                };
+                let parsed = DisplayModeInfo {
+                    width,
+                    height,
+                    refresh_hz: json_f64(mode, "/refreshRate").map(round_refresh),
+                    preferred: id.is_some_and(|id| preferred.contains(&id)),
+                };
+                if id.is_some() && id == current_id {
+                    info.current_mode = Some(parsed.clone());
+                }
+                info.modes.push(parsed);
+            }
+            info.scale = json_f64(item, "/scale");
+            // KScreen rotation flags: 1 none, 2 left, 4 inverted, 8 right.
+            info.transform = json_i64(item, "/rotation").map(|rotation| match rotation {
+                1 => "normal".to_string(),
+                2 => "90".to_string(),
+                4 => "180".to_string(),
+                8 => "270".to_string(),
+                other => other.to_string(),
+            });
+            info.position_x = json_i64(item, "/pos/x");
+            info.position_y = json_i64(item, "/pos/y");
+            info
+        })
+        .collect();
+    Some(outputs)
+}
+
+fn running_processes() -> Vec<String> {
+    list_dir_sorted("/proc")
+        .into_iter()
+        .filter(|path| {
+            path.file_name()
+                .is_some_and(|name| name.to_string_lossy().chars().all(|c| c.is_ascii_digit()))
+        })
+        .filter_map(|path| read_file_optional(path.join("comm")))
+        .collect()
+}
+
+fn detect_compositor(processes: &[String]) -> Option<String> {
+    if env_var_opt("SWAYSOCK").is_some() {
+        return Some("sway".to_string());
+    }
+    if env_var_opt("HYPRLAND_INSTANCE_SIGNATURE").is_some() {
+        return Some("hyprland".to_string());
+    }
+    let desktop = env_var_opt("XDG_CURRENT_DESKTOP")
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    if desktop.contains("gnome") {
+        return Some("mutter".to_string());
+    }
+    if desktop.contains("kde") {
+        return Some("kwin".to_string());
+    }
+    COMPOSITOR_PROCESSES
+        .iter()
+        .find(|(process, _)| processes.iter().any(|p| p == process))
+        .map(|(_, compositor)| compositor.to_string())
+}
+
+fn compositor_outputs(compositor: Option<&str>) -> Option<(String, Vec<DisplayOutputInfo>)> {
+    let from = |source: &str, outputs: Option<Vec<DisplayOutputInfo>>| {
+        outputs.map(|outputs| (source.to_string(), outputs))
+    };
+    let native = match compositor {
+        Some("sway") => from(
+            "swaymsg",
+            run_command_optional("swaymsg", &["-t", "get_outputs", "-r"])
+                .and_then(|out| sway_outputs(&out)),
+        ),
+        Some("hyprland") => from(
+            "hyprctl",
+            run_command_optional("hyprctl", &["-j", "monitors", "all"])
+                .and_then(|out| hyprland_outputs(&out)),
+        ),
+        Some("kwin") => from(
+            "kscreen-doctor",
+            run_command_optional("kscreen-doctor", &["-j"]).and_then(|out| kscreen_outputs(&out)),
+        ),
+        _ => None,
+    };
+    // wlr-randr works on any compositor implementing wlr-output-management.
+    native.or_else(|| {
+        from(
+            "wlr-randr",
+            run_command_optional("wlr-randr", &["--json"]).and_then(|out| wlr_randr_outputs(&out)),
+        )
+    })
+}
+
+pub fn display_info(xdg: &XdgInfo) -> DisplayInfo {
+    let wayland_display = env_var_opt("WAYLAND_DISPLAY");
+    let x_display = env_var_opt("DISPLAY");
+    let session_type = match xdg.session_type() {
+        Some(value) if !value.is_empty() && value != "unspecified" => value.to_string(),
+        _ if wayland_display.is_some() => "wayland".to_string(),
+        _ if x_display.is_some() => "x11".to_string(),
+        _ => "tty".to_string(),
+    };
+    let is_wayland = session_type == "wayland" || wayland_display.is_some();
+    let processes = if is_wayland {
+        running_processes()
+    } else {
+        Vec::new()
+    };
+    let compositor = is_wayland.then(|| detect_compositor(&processes)).flatten();
+    let (outputs_source, outputs) = is_wayland
+        .then(|| compositor_outputs(compositor.as_deref()))
+        .flatten()
+        .map(|(source, outputs)| (Some(source), outputs))
+        .unwrap_or_default();
+    DisplayInfo {
+        xwayland: is_wayland && x_display.is_some() && processes.iter().any(|p| p == "Xwayland"),
+        session_type,
+        wayland_display,
+        x_display,
+        compositor,
+        outputs_source,
+        outputs,
+    }
+}
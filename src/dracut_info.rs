@@ -0,0 +1,99 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::shell_config::ShellVars;
+use crate::utils::{list_dir_sorted, path_string, read_file_optional};
+
+const DRACUT_CONF: &str = "/etc/dracut.conf";
+const DRACUT_CONF_DIRS: [&str; 2] = ["/usr/lib/dracut/dracut.conf.d", "/etc/dracut.conf.d"];
+
+#[derive(Serialize)]
+pub struct DracutInfo {
+    config_files: Vec<String>,
+    add_drivers: Vec<String>,
+    force_drivers: Vec<String>,
+    omit_drivers: Vec<String>,
+    dracutmodules: Vec<String>,
+    add_dracutmodules: Vec<String>,
+    omit_dracutmodules: Vec<String>,
+    install_items: Vec<String>,
+    compress: Option<String>,
+    hostonly: Option<String>,
+    kernel_cmdline: Option<String>,
+    uefi: Option<String>,
+}
+
+impl DracutInfo {
+    pub fn config_files(&self) -> &[String] {
+        &self.config_files
+    }
+
+    pub fn compression(&self) -> Option<&str> {
+        self.compress.as_deref()
+    }
+
+    pub fn drivers(&self) -> Vec<String> {
+        self.add_drivers
+            .iter()
+            .chain(self.force_drivers.iter())
+            .cloned()
+            .collect()
+    }
+
+    pub fn enabled_modules(&self) -> Vec<String> {
+        self.dracutmodules
+            .iter()
+            .chain(self.add_dracutmodules.iter())
+            .filter(|module| !self.omit_dracutmodules.contains(module))
+            .cloned()
+            .collect()
+    }
+}
+
+fn dracut_config_paths() -> Vec<String> {
+    // Drop-ins in /etc override same-named files shipped in /usr/lib; the
+    // merged set is sourced in file name order after the main config.
+    let mut drop_ins = BTreeMap::new();
+    for dir in DRACUT_CONF_DIRS {
+        for path in list_dir_sorted(dir) {
+            if path.extension().is_none_or(|ext| ext != "conf") {
+                continue;
+            }
+            if let Some(name) = path.file_name() {
+                drop_ins.insert(name.to_os_string(), path_string(&path));
+            }
+        }
+    }
+    let mut paths = vec![DRACUT_CONF.to_string()];
+    paths.extend(drop_ins.into_values());
+    paths
+}
+
+pub fn dracut_info() -> Option<DracutInfo> {
+    let mut vars = ShellVars::default();
+    let mut config_files = Vec::new();
+    for path in dracut_config_paths() {
+        if let Some(text) = read_file_optional(&path) {
+            vars.parse(&text);
+            config_files.push(path);
+        }
+    }
+    if config_files.is_empty() {
+        return None;
+    }
+    Some(DracutInfo {
+        config_files,
+        add_drivers: vars.array("add_drivers"),
+        force_drivers: vars.array("force_drivers"),
+        omit_drivers: vars.array("omit_drivers"),
+        dracutmodules: vars.array("dracutmodules"),
+        add_dracutmodules: vars.array("add_dracutmodules"),
+        omit_dracutmodules: vars.array("omit_dracutmodules"),
+        install_items: vars.array("install_items"),
+        compress: vars.non_empty_scalar("compress"),
+        hostonly: vars.non_empty_scalar("hostonly"),
+        kernel_cmdline: vars.non_empty_scalar("kernel_cmdline"),
+        uefi: vars.non_empty_scalar("uefi"),
+    })
+}
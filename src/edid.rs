@@ -0,0 +1,322 @@
+use serde::Serialize;
+
+use crate::utils::redact_serial;
+
+const EDID_HEADER: [u8; 8] = [0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x00];
+const EDID_BLOCK_LEN: usize = 128;
+const CTA_EXTENSION_TAG: u8 = 0x02;
+const HDMI_FORUM_OUI: u32 = 0xc45dd8;
+
+#[derive(Serialize)]
+pub struct EdidInfo {
+    manufacturer: String,
+    product_code: u16,
+    model_name: Option<String>,
+    serial_number: Option<String>,
+    manufacture_year: Option<u16>,
+    manufacture_week: Option<u8>,
+    version: String,
+    physical_width_mm: Option<u32>,
+    physical_height_mm: Option<u32>,
+    preferred_mode: Option<EdidModeInfo>,
+    max_resolution: Option<EdidModeInfo>,
+    hdr_eotfs: Vec<String>,
+    hdr_supported: bool,
+    vrr_min_hz: Option<u32>,
+    vrr_max_hz: Option<u32>,
+    vrr_supported: bool,
+}
+
+#[derive(Clone, Serialize)]
+pub struct EdidModeInfo {
+    width: u32,
+    height: u32,
+    refresh_hz: Option<f64>,
+}
+
+// Manufacturer IDs are three 5-bit letters packed big-endian, 'A' = 1.
+fn manufacturer_id(high: u8, low: u8) -> String {
+    let packed = u16::from_be_bytes([high, low]);
+    [10u16, 5, 0]
+        .iter()
+        .map(|shift| (((packed >> shift) & 0x1f) as u8 + b'A' - 1) as char)
+        .collect()
+}
+
+fn descriptor_text(data: &[u8]) -> Option<String> {
+    let text: String = data
+        .iter()
+        .take_while(|b| **b != 0x0a)
+        .map(|b| *b as char)
+        .collect();
+    Some(text.trim().to_string()).filter(|text| !text.is_empty())
+}
+
+fn detailed_timing(data: &[u8]) -> Option<EdidModeInfo> {
+    let pixel_clock = u16::from_le_bytes([data[0], data[1]]) as f64 * 10_000.0;
+    let width = data[2] as u32 | ((data[4] as u32 >> 4) << 8);
+    let h_blank = data[3] as u32 | ((data[4] as u32 & 0x0f) << 8);
+    let height = data[5] as u32 | ((data[7] as u32 >> 4) << 8);
+    let v_blank = data[6] as u32 | ((data[7] as u32 & 0x0f) << 8);
+    if width == 0 || height == 0 {
+        return None;
+    }
+    let total = ((width + h_blank) * (height + v_blank)) as f64;
+    Some(EdidModeInfo {
+        width,
+        height,
+        refresh_hz: (total > 0.0).then(|| (pixel_clock / total * 100.0).round() / 100.0),
+    })
+}
+
+struct CtaCapabilities {
+    timings: Vec<EdidModeInfo>,
+    hdr_eotfs: Vec<String>,
+    vrr_range: Option<(u32, u32)>,
+}
+
+fn parse_cta_block(block: &[u8], caps: &mut CtaCapabilities) {
+    // An offset of zero means the block has neither data blocks nor timings.
+    let dtd_offset = (block[2] as usize).min(EDID_BLOCK_LEN - 1);
+    if dtd_offset < 4 {
+        return;
+    }
+    let mut offset = 4usize;
+    while offset < dtd_offset {
+        let tag = block[offset] >> 5;
+        let len = (block[offset] & 0x1f) as usize;
+        let Some(payload) = block.get(offset + 1..offset + 1 + len) else {
+            break;
+        };
+        match tag {
+            // Vendor-specific data block; the HDMI Forum one carries VRR limits.
+            3 if len >= 3 => {
+                let oui = u32::from_le_bytes([payload[0], payload[1], payload[2], 0]);
+                if oui == HDMI_FORUM_OUI && len >= 10 {
+                    let min = (payload[8] & 0x3f) as u32;
+                    let max = (((payload[8] as u32) >> 6) << 8) | payload[9] as u32;
+                    if max > 0 {
+                        caps.vrr_range = Some((min, max));
+                    }
+                }
+            }
+            // Extended tag 6: HDR static metadata with the supported EOTFs.
+            7 if len >= 2 && payload[0] == 0x06 => {
+                let names = ["sdr", "hdr", "pq", "hlg"];
+                caps.hdr_eotfs = names
+                    .iter()
+                    .enumerate()
+                    .filter(|(bit, _)| payload[1] & (1 << bit) != 0)
+                    .map(|(_, name)| name.to_string())
+                    .collect();
+            }
+            _ => {}
+        }
+        offset += 1 + len;
+    }
+    let mut offset = dtd_offset;
+    while offset + 18 < EDID_BLOCK_LEN {
+        let descriptor = &block[offset..offset + 18];
+        if descriptor[0] == 0 && descriptor[1] == 0 {
+            break;
+        }
+        caps.timings.extend(detailed_timing(descriptor));
+        offset += 18;
+    }
+}
+
+pub fn decode_edid(data: &[u8], redact_serials: bool) -> Option<EdidInfo> {
+    if data.len() < EDID_BLOCK_LEN || !data.starts_with(&EDID_HEADER) {
+        return None;
+    }
+    let mut timings = Vec::new();
+    let mut model_name = None;
+    let mut serial_text = None;
+    let mut range_limits = None;
+    for offset in [54usize, 72, 90, 108] {
+        let descriptor = &data[offset..offset + 18];
+        if descriptor[0] != 0 || descriptor[1] != 0 {
+            timings.extend(detailed_timing(descriptor));
+            continue;
+        }
+        match descriptor[3] {
+            0xfc => model_name = descriptor_text(&descriptor[5..]),
+            0xff => serial_text = descriptor_text(&descriptor[5..]),
+            0xfd => range_limits = Some((descriptor[5] as u32, descriptor[6] as u32)),
+            _ => {}
+        }
+    }
+    let preferred_mode = timings.first().cloned();
+
+    let mut caps = CtaCapabilities {
+        timings: Vec::new(),
+        hdr_eotfs: Vec::new(),
+        vrr_range: None,
+    };
+    let extensions = data[126] as usize;
+    for index in 1..=extensions {
+        let Some(block) = data.get(index * EDID_BLOCK_LEN..(index + 1) * EDID_BLOCK_LEN) else {
+            break;
+        };
+        if block[0] == CTA_EXTENSION_TAG {
+            parse_cta_block(block, &mut caps);
+        }
+    }
+    timings.extend(caps.timings);
+    let max_resolution = timings
+        .iter()
+        .max_by_key(|mode| (mode.width * mode.height, mode.width))
+        .cloned();
+
+    // Without an HDMI Forum VRR range, a continuous-frequency EDID 1.4 display
+    // with a wide vertical range (FreeSync / Adaptive-Sync) also counts.
+    let continuous_frequency = data[18] == 1 && data[19] >= 4 && data[24] & 0x01 != 0;
+    let vrr_range = caps.vrr_range.or_else(|| {
+        range_limits.filter(|(min, max)| continuous_frequency && max.saturating_sub(*min) >= 10)
+    });
+
+    let serial_number = serial_text
+        .or_else(|| {
+            let serial = u32::from_le_bytes([data[12], data[13], data[14], data[15]]);
+            (serial != 0).then(|| serial.to_string())
+        })
+        .map(|serial| {
+            if redact_serials {
+                redact_serial(&serial)
+            } else {
+                serial
+            }
+        });
+    let size_mm = |cm: u8| (cm > 0).then_some(cm as u32 * 10);
+    let hdr_supported = caps
+        .hdr_eotfs
+        .iter()
+        .any(|eotf| eotf == "pq" || eotf == "hlg");
+
+    Some(EdidInfo {
+        manufacturer: manufacturer_id(data[8], data[9]),
+        product_code: u16::from_le_bytes([data[10], data[11]]),
+        model_name,
+        serial_number,
+        manufacture_year: (data[17] > 0).then(|| data[17] as u16 + 1990),
+        manufacture_week: (data[16] > 0 && data[16] <= 54).then_some(data[16]),
+        version: format!("{}.{}", data[18], data[19]),
+        physical_width_mm: size_mm(data[21]),
+        physical_height_mm: size_mm(data[22]),
+        preferred_mode,
+        max_resolution,
+        hdr_eotfs: caps.hdr_eotfs,
+        hdr_supported,
+        vrr_min_hz: vrr_range.map(|(min, _)| min),
+        vrr_max_hz: vrr_range.map(|(_, max)| max),
+        vrr_supported: vrr_range.is_some(),
+    })
+}
+
+// xrandr --verbose prints the EDID as indented lines of hex digits.
+pub fn decode_edid_hex(hex: &str, redact_serials: bool) -> Option<EdidInfo> {
+    let digits: Vec<u8> = hex.bytes().filter(u8::is_ascii_hexdigit).collect();
+    let bytes: Option<Vec<u8>> = digits
+        .chunks_exact(2)
+        .map(|pair| u8::from_str_radix(std::str::from_utf8(pair).ok()?, 16).ok())
+        .collect();
+    decode_edid(&bytes?, redact_serials)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn descriptor(block: &mut [u8], offset: usize, bytes: &[u8]) {
+        block[offset..offset + bytes.len()].copy_from_slice(bytes);
+    }
+
+    // A 1.4 EDID for a "DEL" 27" panel with a 1920x1080@60 preferred mode, a
+    // 48-144 Hz range limit and a CTA extension with a 3840x2160@60 timing and
+    // PQ support.
+    fn fixture() -> Vec<u8> {
+        let mut data = vec![0u8; EDID_BLOCK_LEN * 2];
+        data[..8].copy_from_slice(&EDID_HEADER);
+        data[8..10].copy_from_slice(&0x10acu16.to_be_bytes());
+        data[10..12].copy_from_slice(&0xa0b1u16.to_le_bytes());
+        data[12..16].copy_from_slice(&123_456u32.to_le_bytes());
+        data[16] = 12;
+        data[17] = 32;
+        data[18] = 1;
+        data[19] = 4;
+        data[21] = 60;
+        data[22] = 34;
+        data[24] = 0x01;
+        descriptor(
+            &mut data,
+            54,
+            &[0x02, 0x3a, 0x80, 0x18, 0x71, 0x38, 0x2d, 0x40],
+        );
+        descriptor(&mut data, 72, b"\0\0\0\xfc\0DELL U2720Q\n  ");
+        descriptor(&mut data, 90, &[0, 0, 0, 0xfd, 0, 48, 144]);
+        data[126] = 1;
+
+        let cta = &mut data[EDID_BLOCK_LEN..];
+        cta[0] = CTA_EXTENSION_TAG;
+        cta[1] = 3;
+        cta[2] = 8;
+        descriptor(cta, 4, &[0xe3, 0x06, 0x05, 0x01]);
+        descriptor(cta, 8, &[0x08, 0xe8, 0x00, 0x30, 0xf2, 0x70, 0x5a, 0x80]);
+        data
+    }
+
+    #[test]
+    fn decodes_base_block() {
+        let edid = decode_edid(&fixture(), false).unwrap();
+        assert_eq!(edid.manufacturer, "DEL");
+        assert_eq!(edid.product_code, 0xa0b1);
+        assert_eq!(edid.model_name.as_deref(), Some("DELL U2720Q"));
+        assert_eq!(edid.serial_number.as_deref(), Some("123456"));
+        assert_eq!(edid.manufacture_year, Some(2022));
+        assert_eq!(edid.manufacture_week, Some(12));
+        assert_eq!(edid.version, "1.4");
+        assert_eq!(edid.physical_width_mm, Some(600));
+        assert_eq!(edid.physical_height_mm, Some(340));
+        let preferred = edid.preferred_mode.unwrap();
+        assert_eq!((preferred.width, preferred.height), (1920, 1080));
+        assert_eq!(preferred.refresh_hz, Some(60.0));
+    }
+
+    #[test]
+    fn decodes_cta_extension() {
+        let edid = decode_edid(&fixture(), false).unwrap();
+        let max = edid.max_resolution.unwrap();
+        assert_eq!(
+            (max.width, max.height, max.refresh_hz),
+            (3840, 2160, Some(60.0))
+        );
+        assert_eq!(edid.hdr_eotfs, ["sdr", "pq"]);
+        assert!(edid.hdr_supported);
+        assert_eq!((edid.vrr_min_hz, edid.vrr_max_hz), (Some(48), Some(144)));
+        assert!(edid.vrr_supported);
+    }
+
+    #[test]
+    fn redacts_serial_and_rejects_bad_header() {
+        let edid = decode_edid(&fixture(), true).unwrap();
+        assert_eq!(edid.serial_number.as_deref(), Some("**3456"));
+
+        let mut data = fixture();
+        data[0] = 0xff;
+        assert!(decode_edid(&data, false).is_none());
+        assert!(decode_edid(&fixture()[..100], false).is_none());
+    }
+
+    #[test]
+    fn decodes_xrandr_hex() {
+        let hex: String = fixture()
+            .chunks(16)
+            .map(|line| {
+                let digits: String = line.iter().map(|b| format!("{b:02x}")).collect();
+                format!("\t\t{digits}\n")
+            })
+            .collect();
+        let edid = decode_edid_hex(&hex, false).unwrap();
+        assert_eq!(edid.model_name.as_deref(), Some("DELL U2720Q"));
+    }
+}
@@ -0,0 +1,487 @@
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::mounts_info::{MountEntryInfo, MountsInfo};
+use crate::utils::{find_in_path, run_command_optional, run_command_timeout};
+
+const PSEUDO_FILESYSTEMS: [&str; 22] = [
+    "autofs",
+    "binfmt_misc",
+    "bpf",
+    "cgroup",
+    "cgroup2",
+    "configfs",
+    "debugfs",
+    "devpts",
+    "devtmpfs",
+    "efivarfs",
+    "fusectl",
+    "hugetlbfs",
+    "mqueue",
+    "nsfs",
+    "proc",
+    "pstore",
+    "ramfs",
+    "rpc_pipefs",
+    "securityfs",
+    "selinuxfs",
+    "sysfs",
+    "tracefs",
+];
+
+// statfs on these can block for minutes when the server is unreachable.
+const NETWORK_FILESYSTEMS: [&str; 12] = [
+    "9p",
+    "afs",
+    "ceph",
+    "cifs",
+    "davfs",
+    "glusterfs",
+    "lustre",
+    "ncpfs",
+    "nfs",
+    "nfs4",
+    "smb3",
+    "smbfs",
+];
+const STATFS_TIMEOUT: Duration = Duration::from_secs(2);
+
+pub struct FilesystemThresholds {
+    pub warn_percent: f64,
+    pub critical_percent: f64,
+}
+
+#[derive(Serialize)]
+pub struct FilesystemInfo {
+    usage: Vec<FilesystemUsageInfo>,
+    btrfs: Vec<BtrfsFilesystemInfo>,
+    zfs: Option<ZfsInfo>,
+    warnings: Vec<FilesystemWarningInfo>,
+}
+
+#[derive(Serialize)]
+pub struct FilesystemUsageInfo {
+    mount_point: String,
+    fstype: String,
+    source: String,
+    size_bytes: u64,
+    used_bytes: u64,
+    available_bytes: u64,
+    used_percent: Option<f64>,
+    inodes_total: u64,
+    inodes_used: u64,
+    inodes_free: u64,
+    inodes_used_percent: Option<f64>,
+}
+
+#[derive(Serialize)]
+pub struct FilesystemWarningInfo {
+    mount_point: String,
+    kind: String,
+    level: String,
+    used_percent: f64,
+}
+
+#[derive(Serialize)]
+pub struct BtrfsFilesystemInfo {
+    mount_point: String,
+    source: String,
+    compression: Option<String>,
+    devices: Vec<BtrfsDeviceInfo>,
+    profiles: Vec<BtrfsProfileInfo>,
+    subvolumes: Vec<BtrfsSubvolumeInfo>,
+}
+
+#[derive(Serialize)]
+pub struct BtrfsDeviceInfo {
+    devid: String,
+    path: String,
+    size: String,
+    used: String,
+}
+
+#[derive(Serialize)]
+pub struct BtrfsProfileInfo {
+    block_group: String,
+    profile: String,
+    total_bytes: Option<u64>,
+    used_bytes: Option<u64>,
+}
+
+#[derive(Serialize)]
+pub struct BtrfsSubvolumeInfo {
+    id: String,
+    top_level: String,
+    path: String,
+}
+
+#[derive(Serialize)]
+pub struct ZfsInfo {
+    pools: Vec<ZfsPoolInfo>,
+    datasets: Vec<ZfsDatasetInfo>,
+}
+
+#[derive(Serialize)]
+pub struct ZfsPoolInfo {
+    name: String,
+    size_bytes: Option<u64>,
+    allocated_bytes: Option<u64>,
+    free_bytes: Option<u64>,
+    fragmentation_percent: Option<u64>,
+    capacity_percent: Option<u64>,
+    health: String,
+}
+
+#[derive(Serialize)]
+pub struct ZfsDatasetInfo {
+    name: String,
+    used_bytes: Option<u64>,
+    available_bytes: Option<u64>,
+    referenced_bytes: Option<u64>,
+    mountpoint: String,
+    compression: String,
+    compressratio: String,
+}
+
+fn percent(used: u64, total: u64) -> Option<f64> {
+    if total == 0 {
+        return None;
+    }
+    Some((used as f64 / total as f64 * 1000.0).round() / 10.0)
+}
+
+fn is_reportable(entry: &MountEntryInfo) -> bool {
+    let fstype = entry.fstype();
+    !PSEUDO_FILESYSTEMS.contains(&fstype)
+        && !NETWORK_FILESYSTEMS.contains(&fstype)
+        && !fstype.starts_with("fuse.")
+}
+
+fn parse_statfs_line(line: &str) -> Option<[u64; 6]> {
+    let mut parts = line.split_whitespace();
+    let mut values = [0u64; 6];
+    for value in values.iter_mut() {
+        *value = parts.next()?.parse().ok()?;
+    }
+    Some(values)
+}
+
+// `stat -f` is a thin wrapper around statfs(2). Each mount gets its own call
+// with a timeout so a single hung mount cannot stall the whole run; the short
+// options are understood by both GNU coreutils and busybox.
+fn statfs(mount_point: &str) -> Option<[u64; 6]> {
+    let output = run_command_timeout(
+        "stat",
+        &["-f", "-c", "%S %b %f %a %c %d", "--", mount_point],
+        STATFS_TIMEOUT,
+    )?;
+    parse_statfs_line(&output)
+}
+
+fn filesystem_usage(mounts: &[&MountEntryInfo]) -> Vec<FilesystemUsageInfo> {
+    mounts
+        .iter()
+        .filter_map(|entry| {
+            let [block_size, blocks, free, available, files, files_free] =
+                statfs(entry.mount_point())?;
+            if blocks == 0 {
+                return None;
+            }
+            let size_bytes = blocks.saturating_mul(block_size);
+            let used_bytes = blocks.saturating_sub(free).saturating_mul(block_size);
+            let available_bytes = available.saturating_mul(block_size);
+            let inodes_used = files.saturating_sub(files_free);
+            Some(FilesystemUsageInfo {
+                mount_point: entry.mount_point().to_string(),
+                fstype: entry.fstype().to_string(),
+                source: entry.source().to_string(),
+                size_bytes,
+                used_bytes,
+                available_bytes,
+                // Like df, measure against the space usable by unprivileged users.
+                used_percent: percent(used_bytes, used_bytes + available_bytes),
+                inodes_total: files,
+                inodes_used,
+                inodes_free: files_free,
+                inodes_used_percent: percent(inodes_used, files),
+            })
+        })
+        .collect()
+}
+
+fn threshold_warnings(
+    usage: &[FilesystemUsageInfo],
+    thresholds: &FilesystemThresholds,
+) -> Vec<FilesystemWarningInfo> {
+    let mut warnings = Vec::new();
+    for fs in usage {
+        for (kind, value) in [
+            ("space", fs.used_percent),
+            ("inodes", fs.inodes_used_percent),
+        ] {
+            let Some(value) = value else {
+                continue;
+            };
+            let level = if value >= thresholds.critical_percent {
+                "critical"
+            } else if value >= thresholds.warn_percent {
+                "warning"
+            } else {
+                continue;
+            };
+            warnings.push(FilesystemWarningInfo {
+                mount_point: fs.mount_point.clone(),
+                kind: kind.to_string(),
+                level: level.to_string(),
+                used_percent: value,
+            });
+        }
+    }
+    warnings
+}
+
+fn parse_btrfs_df(output: &str) -> Vec<BtrfsProfileInfo> {
+    // Data, single: total=8388608, used=0
+    output
+        .lines()
+        .filter_map(|line| {
+            let (group, rest) = line.split_once(':')?;
+            let (block_group, profile) = group.split_once(", ")?;
+            let field = |key: &str| {
+                rest.split(',')
+                    .find_map(|part| part.trim().strip_prefix(key))
+                    .and_then(|value| value.parse().ok())
+            };
+            Some(BtrfsProfileInfo {
+                block_group: block_group.trim().to_string(),
+                profile: profile.trim().to_string(),
+                total_bytes: field("total="),
+                used_bytes: field("used="),
+            })
+        })
+        .collect()
+}
+
+fn parse_btrfs_show(output: &str) -> Vec<BtrfsDeviceInfo> {
+    // devid    1 size 100.00GiB used 50.00GiB path /dev/sda2
+    output
+        .lines()
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.first() != Some(&"devid") {
+                return None;
+            }
+            let after = |key: &str| {
+                parts
+                    .iter()
+                    .position(|part| *part == key)
+                    .and_then(|pos| parts.get(pos + 1))
+                    .map(|value| value.to_string())
+                    .unwrap_or_default()
+            };
+            Some(BtrfsDeviceInfo {
+                devid: after("devid"),
+                size: after("size"),
+                used: after("used"),
+                path: after("path"),
+            })
+        })
+        .collect()
+}
+
+fn parse_btrfs_subvolumes(output: &str) -> Vec<BtrfsSubvolumeInfo> {
+    // ID 256 gen 1234 top level 5 path @home
+    output
+        .lines()
+        .filter_map(|line| {
+            let id = line.strip_prefix("ID ")?.split_whitespace().next()?;
+            let top_level = line
+                .split(" top level ")
+                .nth(1)?
+                .split_whitespace()
+                .next()?;
+            let path = line.split_once(" path ")?.1;
+            Some(BtrfsSubvolumeInfo {
+                id: id.to_string(),
+                top_level: top_level.to_string(),
+                path: path.to_string(),
+            })
+        })
+        .collect()
+}
+
+fn btrfs_info(mounts: &[&MountEntryInfo]) -> Vec<BtrfsFilesystemInfo> {
+    if find_in_path("btrfs").is_none() {
+        return Vec::new();
+    }
+    let mut seen = Vec::new();
+    mounts
+        .iter()
+        .filter(|entry| entry.fstype() == "btrfs")
+        .filter(|entry| {
+            // Report each filesystem once, through its first mount.
+            if seen.contains(&entry.source()) {
+                return false;
+            }
+            seen.push(entry.source());
+            true
+        })
+        .map(|entry| {
+            let mount_point = entry.mount_point();
+            let btrfs = |args: &[&str]| {
+                let mut full = args.to_vec();
+                full.push(mount_point);
+                run_command_optional("btrfs", &full).unwrap_or_default()
+            };
+            BtrfsFilesystemInfo {
+                mount_point: mount_point.to_string(),
+                source: entry.source().to_string(),
+                compression: entry.super_options().iter().find_map(|option| {
+                    option
+                        .strip_prefix("compress=")
+                        .or_else(|| option.strip_prefix("compress-force="))
+                        .map(String::from)
+                }),
+                devices: parse_btrfs_show(&btrfs(&["filesystem", "show"])),
+                profiles: parse_btrfs_df(&btrfs(&["filesystem", "df", "-b"])),
+                subvolumes: parse_btrfs_subvolumes(&btrfs(&["subvolume", "list"])),
+            }
+        })
+        .collect()
+}
+
+fn zfs_info() -> Option<ZfsInfo> {
+    find_in_path("zpool")?;
+    let number = |value: Option<&&str>| value.and_then(|v| v.trim_end_matches('%').parse().ok());
+    let pools = run_command_optional(
+        "zpool",
+        &[
+            "list",
+            "-H",
+            "-p",
+            "-o",
+            "name,size,alloc,free,frag,cap,health",
+        ],
+    )
+    .unwrap_or_default()
+    .lines()
+    .map(|line| {
+        let fields: Vec<&str> = line.split('\t').collect();
+        ZfsPoolInfo {
+            name: fields.first().map(|v| v.to_string()).unwrap_or_default(),
+            size_bytes: number(fields.get(1)),
+            allocated_bytes: number(fields.get(2)),
+            free_bytes: number(fields.get(3)),
+            fragmentation_percent: number(fields.get(4)),
+            capacity_percent: number(fields.get(5)),
+            health: fields.get(6).map(|v| v.to_string()).unwrap_or_default(),
+        }
+    })
+    .collect();
+    let datasets = run_command_optional(
+        "zfs",
+        &[
+            "list",
+            "-H",
+            "-p",
+            "-o",
+            "name,used,avail,refer,mountpoint,compression,compressratio",
+        ],
+    )
+    .unwrap_or_default()
+    .lines()
+    .map(|line| {
+        let fields: Vec<&str> = line.split('\t').collect();
+        let text = |index: usize| fields.get(index).map(|v| v.to_string()).unwrap_or_default();
+        ZfsDatasetInfo {
+            name: text(0),
+            used_bytes: number(fields.get(1)),
+            available_bytes: number(fields.get(2)),
+            referenced_bytes: number(fields.get(3)),
+            mountpoint: text(4),
+            compression: text(5),
+            compressratio: text(6),
+        }
+    })
+    .collect();
+    Some(ZfsInfo { pools, datasets })
+}
+
+pub fn filesystem_info(mounts: &MountsInfo, thresholds: &FilesystemThresholds) -> FilesystemInfo {
+    let reportable: Vec<&MountEntryInfo> = mounts
+        .entries()
+        .iter()
+        .filter(|entry| is_reportable(entry))
+        .collect();
+    let usage = filesystem_usage(&reportable);
+    let warnings = threshold_warnings(&usage, thresholds);
+    FilesystemInfo {
+        btrfs: btrfs_info(&reportable),
+        zfs: zfs_info(),
+        usage,
+        warnings,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_statfs_line() {
+        assert_eq!(
+            parse_statfs_line("4096 65536 1024 512 16384 16000"),
+            Some([4096, 65536, 1024, 512, 16384, 16000])
+        );
+        assert_eq!(parse_statfs_line("4096 65536 1024"), None);
+        assert_eq!(parse_statfs_line("4096 ? 1024 512 16384 16000"), None);
+    }
+
+    #[test]
+    fn parses_btrfs_df() {
+        let output = "\
+Data, single: total=8388608, used=4096
+System, DUP: total=8388608, used=16384
+Metadata, DUP: total=268435456, used=131072
+GlobalReserve, single: total=3670016, used=0
+";
+        let profiles = parse_btrfs_df(output);
+        assert_eq!(profiles.len(), 4);
+        assert_eq!(profiles[1].block_group, "System");
+        assert_eq!(profiles[1].profile, "DUP");
+        assert_eq!(profiles[1].total_bytes, Some(8388608));
+        assert_eq!(profiles[1].used_bytes, Some(16384));
+    }
+
+    #[test]
+    fn parses_btrfs_show() {
+        let output = "\
+Label: 'root'  uuid: 5f3c6a7e-1d2b-4c8e-9a0f-123456789abc
+\tTotal devices 2 FS bytes used 50.00GiB
+\tdevid    1 size 100.00GiB used 52.03GiB path /dev/sda2
+\tdevid    2 size 100.00GiB used 52.03GiB path /dev/sdb2
+";
+        let devices = parse_btrfs_show(output);
+        assert_eq!(devices.len(), 2);
+        assert_eq!(devices[1].devid, "2");
+        assert_eq!(devices[1].size, "100.00GiB");
+        assert_eq!(devices[1].used, "52.03GiB");
+        assert_eq!(devices[1].path, "/dev/sdb2");
+    }
+
+    #[test]
+    fn parses_btrfs_subvolumes() {
+        let output = "\
+ID 256 gen 1234 top level 5 path @
+ID 257 gen 1230 top level 5 path @home
+ID 300 gen 1100 top level 256 path .snapshots/1/my snapshot
+";
+        let subvolumes = parse_btrfs_subvolumes(output);
+        assert_eq!(subvolumes.len(), 3);
+        assert_eq!(subvolumes[1].id, "257");
+        assert_eq!(subvolumes[1].path, "@home");
+        assert_eq!(subvolumes[2].top_level, "256");
+        assert_eq!(subvolumes[2].path, ".snapshots/1/my snapshot");
+    }
+}
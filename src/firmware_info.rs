@@ -0,0 +1,236 @@
+use std::fs;
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::utils::{list_dir_sorted, read_file_optional};
+
+const EFI_DIR: &str = "/sys/firmware/efi";
+const EFIVARS_DIR: &str = "/sys/firmware/efi/efivars";
+const EFI_GLOBAL_GUID: &str = "8be4df61-93ca-11d2-aa0d-00e098032b8c";
+
+#[derive(Serialize)]
+pub struct FirmwareInfo {
+    boot_mode: String,
+    platform_size: Option<String>,
+    secure_boot: Option<bool>,
+    setup_mode: Option<bool>,
+    boot_current: Option<String>,
+    boot_order: Vec<String>,
+    boot_entries: Vec<EfiBootEntryInfo>,
+    tpm: Vec<TpmInfo>,
+}
+
+#[derive(Serialize)]
+pub struct EfiBootEntryInfo {
+    id: String,
+    description: String,
+    active: bool,
+    partuuid: Option<String>,
+    path: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct TpmInfo {
+    name: String,
+    version: Option<String>,
+}
+
+// efivarfs files start with a 4-byte attribute mask before the payload.
+fn read_efivar(name: &str) -> Option<Vec<u8>> {
+    let path = format!("{EFIVARS_DIR}/{name}-{EFI_GLOBAL_GUID}");
+    let data = fs::read(path).ok()?;
+    data.get(4..).map(<[u8]>::to_vec)
+}
+
+fn efivar_flag(name: &str) -> Option<bool> {
+    read_efivar(name)?.first().map(|value| *value == 1)
+}
+
+fn read_u16_list(data: &[u8]) -> Vec<u16> {
+    data.chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .collect()
+}
+
+fn decode_utf16(data: &[u8]) -> (String, usize) {
+    let units: Vec<u16> = read_u16_list(data)
+        .into_iter()
+        .take_while(|unit| *unit != 0)
+        .collect();
+    let consumed = (units.len() + 1) * 2;
+    (String::from_utf16_lossy(&units), consumed.min(data.len()))
+}
+
+// GUIDs are stored with their first three fields little-endian.
+fn format_guid(bytes: &[u8]) -> String {
+    let hex = |range: &[u8]| range.iter().map(|b| format!("{b:02x}")).collect::<String>();
+    let swapped = |range: &[u8]| hex(&range.iter().rev().copied().collect::<Vec<u8>>());
+    format!(
+        "{}-{}-{}-{}-{}",
+        swapped(&bytes[0..4]),
+        swapped(&bytes[4..6]),
+        swapped(&bytes[6..8]),
+        hex(&bytes[8..10]),
+        hex(&bytes[10..16])
+    )
+}
+
+fn parse_device_path(data: &[u8]) -> (Option<String>, Option<String>) {
+    let mut partuuid = None;
+    let mut path = None;
+    let mut offset = 0usize;
+    while let Some(header) = data.get(offset..offset + 4) {
+        let node_type = header[0];
+        let sub_type = header[1];
+        let length = u16::from_le_bytes([header[2], header[3]]) as usize;
+        if node_type == 0x7f || length < 4 {
+            break;
+        }
+        let Some(node) = data.get(offset + 4..offset + length) else {
+            break;
+        };
+        match (node_type, sub_type) {
+            // Media / hard drive: partition number, start, size, signature,
+            // format and signature type; type 2 is a GPT partition GUID.
+            (0x04, 0x01) if node.len() >= 38 && node[37] == 2 => {
+                partuuid = Some(format_guid(&node[20..36]));
+            }
+            (0x04, 0x04) => {
+                path = Some(decode_utf16(node).0);
+            }
+            _ => {}
+        }
+        offset += length;
+    }
+    (partuuid, path)
+}
+
+fn parse_load_option(id: String, data: &[u8]) -> Option<EfiBootEntryInfo> {
+    let attributes = u32::from_le_bytes(data.get(0..4)?.try_into().ok()?);
+    let path_length = u16::from_le_bytes(data.get(4..6)?.try_into().ok()?) as usize;
+    let (description, consumed) = decode_utf16(data.get(6..)?);
+    let path_start = 6 + consumed;
+    let device_path = data
+        .get(path_start..path_start + path_length)
+        .unwrap_or_default();
+    let (partuuid, path) = parse_device_path(device_path);
+    Some(EfiBootEntryInfo {
+        id,
+        description,
+        active: attributes & 1 == 1,
+        partuuid,
+        path,
+    })
+}
+
+fn efi_boot_entries() -> Vec<EfiBootEntryInfo> {
+    let suffix = format!("-{EFI_GLOBAL_GUID}");
+    list_dir_sorted(EFIVARS_DIR)
+        .into_iter()
+        .filter_map(|path| {
+            let name = path.file_name()?.to_string_lossy().into_owned();
+            let id = name.strip_prefix("Boot")?.strip_suffix(&suffix)?;
+            if id.len() != 4 || !id.chars().all(|c| c.is_ascii_hexdigit()) {
+                return None;
+            }
+            let data = fs::read(&path).ok()?;
+            parse_load_option(id.to_string(), data.get(4..)?)
+        })
+        .collect()
+}
+
+fn tpm_info() -> Vec<TpmInfo> {
+    list_dir_sorted("/sys/class/tpm")
+        .into_iter()
+        .filter_map(|path| {
+            let name = path.file_name()?.to_string_lossy().into_owned();
+            let version = read_file_optional(path.join("tpm_version_major"))
+                .map(|major| format!("{major}.0"))
+                .or_else(|| {
+                    let caps = read_file_optional(path.join("device/caps"))?;
+                    caps.lines()
+                        .find_map(|line| line.strip_prefix("TCG version:"))
+                        .map(|value| value.trim().to_string())
+                });
+            Some(TpmInfo { name, version })
+        })
+        .collect()
+}
+
+pub fn firmware_info() -> FirmwareInfo {
+    let uefi = Path::new(EFI_DIR).is_dir();
+    let format_id = |id: u16| format!("{id:04X}");
+    FirmwareInfo {
+        boot_mode: if uefi { "uefi" } else { "bios" }.to_string(),
+        platform_size: read_file_optional(format!("{EFI_DIR}/fw_platform_size")),
+        secure_boot: efivar_flag("SecureBoot"),
+        setup_mode: efivar_flag("SetupMode"),
+        boot_current: read_efivar("BootCurrent")
+            .and_then(|data| read_u16_list(&data).first().copied())
+            .map(format_id),
+        boot_order: read_efivar("BootOrder")
+            .map(|data| read_u16_list(&data).into_iter().map(format_id).collect())
+            .unwrap_or_default(),
+        boot_entries: efi_boot_entries(),
+        tpm: tpm_info(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Boot0000-8be4df61-... laid out as efivarfs returns it after `bootctl install`,
+    // including the leading attribute mask.
+    const BOOT0000_FIXTURE: &str = "\
+07 00 00 00 01 00 00 00 74 00 4c 00 69 00 6e 00
+75 00 78 00 20 00 42 00 6f 00 6f 00 74 00 20 00
+4d 00 61 00 6e 00 61 00 67 00 65 00 72 00 00 00
+04 01 2a 00 01 00 00 00 00 08 00 00 00 00 00 00
+00 00 10 00 00 00 00 00 3e 9a 8c 4d 1b 5f 2a 4c
+9e 7d 0a 1b 2c 3d 4e 5f 02 02 04 04 46 00 5c 00
+45 00 46 00 49 00 5c 00 73 00 79 00 73 00 74 00
+65 00 6d 00 64 00 5c 00 73 00 79 00 73 00 74 00
+65 00 6d 00 64 00 2d 00 62 00 6f 00 6f 00 74 00
+78 00 36 00 34 00 2e 00 65 00 66 00 69 00 00 00
+7f ff 04 00
+";
+
+    fn fixture_bytes() -> Vec<u8> {
+        BOOT0000_FIXTURE
+            .split_whitespace()
+            .map(|byte| u8::from_str_radix(byte, 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn parses_boot_load_option() {
+        let data = fixture_bytes();
+        let entry = parse_load_option("0000".to_string(), &data[4..]).unwrap();
+        assert_eq!(entry.id, "0000");
+        assert_eq!(entry.description, "Linux Boot Manager");
+        assert!(entry.active);
+        assert_eq!(
+            entry.partuuid.as_deref(),
+            Some("4d8c9a3e-5f1b-4c2a-9e7d-0a1b2c3d4e5f")
+        );
+        assert_eq!(
+            entry.path.as_deref(),
+            Some("\\EFI\\systemd\\systemd-bootx64.efi")
+        );
+    }
+
+    #[test]
+    fn stops_at_truncated_device_path() {
+        let data = fixture_bytes();
+        // The device path starts at byte 44 of the payload; cut inside the file node.
+        let (partuuid, path) = parse_device_path(&data[4 + 44..data.len() - 40]);
+        assert_eq!(
+            partuuid.as_deref(),
+            Some("4d8c9a3e-5f1b-4c2a-9e7d-0a1b2c3d4e5f")
+        );
+        assert_eq!(path, None);
+        assert!(parse_load_option("0001".to_string(), &data[4..8]).is_none());
+    }
+}
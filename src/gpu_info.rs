@@ -0,0 +1,273 @@
+use std::fs;
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::edid::{decode_edid, EdidInfo};
+use crate::pci_info::PciBusInfo;
+use crate::utils::{list_dir_sorted, read_file_optional, read_file_string, run_command_optional};
+
+const DRM_DIR: &str = "/sys/class/drm";
+
+#[derive(Serialize)]
+pub struct GpuInfo {
+    cards: Vec<GpuCardInfo>,
+    opengl: Option<OpenGlInfo>,
+    vulkan: Vec<VulkanDeviceInfo>,
+}
+
+#[derive(Serialize)]
+pub struct GpuCardInfo {
+    name: String,
+    driver: Option<String>,
+    pci_slot: Option<String>,
+    pci_description: Option<String>,
+    vendor_id: Option<String>,
+    device_id: Option<String>,
+    boot_vga: Option<bool>,
+    render_nodes: Vec<String>,
+    runtime_status: Option<String>,
+    vram_total_bytes: Option<u64>,
+    vram_used_bytes: Option<u64>,
+    busy_percent: Option<u64>,
+    performance_level: Option<String>,
+    power_profile: Option<String>,
+    core_clock_levels: Vec<GpuClockLevelInfo>,
+    memory_clock_levels: Vec<GpuClockLevelInfo>,
+    current_freq_mhz: Option<u64>,
+    max_freq_mhz: Option<u64>,
+    connectors: Vec<DrmConnectorInfo>,
+}
+
+#[derive(Serialize)]
+pub struct GpuClockLevelInfo {
+    level: u32,
+    mhz: Option<u64>,
+    active: bool,
+}
+
+#[derive(Serialize)]
+pub struct DrmConnectorInfo {
+    name: String,
+    status: Option<String>,
+    enabled: Option<bool>,
+    dpms: Option<String>,
+    modes: Vec<String>,
+    edid: Option<EdidInfo>,
+}
+
+#[derive(Serialize)]
+pub struct OpenGlInfo {
+    vendor: Option<String>,
+    renderer: Option<String>,
+    version: Option<String>,
+    core_profile_version: Option<String>,
+    direct_rendering: Option<bool>,
+}
+
+#[derive(Serialize)]
+pub struct VulkanDeviceInfo {
+    name: String,
+    device_type: Option<String>,
+    api_version: Option<String>,
+    driver_name: Option<String>,
+    driver_info: Option<String>,
+    vendor_id: Option<String>,
+    device_id: Option<String>,
+}
+
+fn link_name(path: &Path) -> Option<String> {
+    let target = fs::read_link(path).ok()?;
+    Some(target.file_name()?.to_string_lossy().into_owned())
+}
+
+fn is_card_name(name: &str) -> bool {
+    name.strip_prefix("card")
+        .is_some_and(|rest| !rest.is_empty() && rest.chars().all(|c| c.is_ascii_digit()))
+}
+
+// "0: 500Mhz\n1: 1800Mhz *"
+fn parse_clock_levels(text: &str) -> Vec<GpuClockLevelInfo> {
+    text.lines()
+        .filter_map(|line| {
+            let (level, rest) = line.split_once(':')?;
+            let digits: String = rest
+                .trim()
+                .chars()
+                .take_while(char::is_ascii_digit)
+                .collect();
+            Some(GpuClockLevelInfo {
+                level: level.trim().parse().ok()?,
+                mhz: digits.parse().ok(),
+                active: rest.trim_end().ends_with('*'),
+            })
+        })
+        .collect()
+}
+
+// The active row of pp_power_profile_mode is marked with '*', e.g.
+// "  1 3D_FULL_SCREEN*:" or "  1 3D_FULL_SCREEN *:".
+fn active_power_profile(text: &str) -> Option<String> {
+    text.lines()
+        .filter(|line| line.contains('*'))
+        .find_map(|line| {
+            let name = line.split_whitespace().nth(1)?;
+            let name = name.trim_end_matches([':', '*']);
+            Some(name.to_string()).filter(|name| !name.is_empty())
+        })
+}
+
+fn drm_connectors(card: &str, redact_serials: bool) -> Vec<DrmConnectorInfo> {
+    let prefix = format!("{card}-");
+    list_dir_sorted(DRM_DIR)
+        .into_iter()
+        .filter_map(|path| {
+            let dir_name = path.file_name()?.to_string_lossy().into_owned();
+            let name = dir_name.strip_prefix(&prefix)?.to_string();
+            let edid = fs::read(path.join("edid"))
+                .ok()
+                .and_then(|data| decode_edid(&data, redact_serials));
+            Some(DrmConnectorInfo {
+                name,
+                status: read_file_optional(path.join("status")),
+                enabled: read_file_optional(path.join("enabled")).map(|v| v == "enabled"),
+                dpms: read_file_optional(path.join("dpms")),
+                modes: read_file_string(path.join("modes"))
+                    .lines()
+                    .map(String::from)
+                    .collect(),
+                edid,
+            })
+        })
+        .collect()
+}
+
+fn gpu_card(path: &Path, name: String, lspci: &[PciBusInfo], redact_serials: bool) -> GpuCardInfo {
+    let device = path.join("device");
+    let read = |file: &str| read_file_optional(device.join(file));
+    let pci_address = link_name(&device).filter(|addr| addr.contains(':'));
+    // lspci prints slots without the PCI domain unless it is non-zero.
+    let pci_slot = pci_address
+        .as_deref()
+        .map(|addr| addr.strip_prefix("0000:").unwrap_or(addr).to_string());
+    let pci_description = pci_slot.as_deref().and_then(|slot| {
+        lspci
+            .iter()
+            .find(|dev| dev.slot() == slot)
+            .map(|dev| dev.device_description().to_string())
+    });
+    let render_nodes = list_dir_sorted(device.join("drm"))
+        .into_iter()
+        .filter_map(|node| node.file_name().map(|n| n.to_string_lossy().into_owned()))
+        .filter(|node| node.starts_with("renderD"))
+        .map(|node| format!("/dev/dri/{node}"))
+        .collect();
+    let freq = |file: &str| read_file_optional(path.join(file)).and_then(|v| v.parse().ok());
+    GpuCardInfo {
+        driver: link_name(&device.join("driver")),
+        vendor_id: read("vendor").map(|v| v.trim_start_matches("0x").to_string()),
+        device_id: read("device").map(|v| v.trim_start_matches("0x").to_string()),
+        boot_vga: read("boot_vga").map(|v| v == "1"),
+        render_nodes,
+        runtime_status: read("power/runtime_status"),
+        vram_total_bytes: read("mem_info_vram_total").and_then(|v| v.parse().ok()),
+        vram_used_bytes: read("mem_info_vram_used").and_then(|v| v.parse().ok()),
+        busy_percent: read("gpu_busy_percent").and_then(|v| v.parse().ok()),
+        performance_level: read("power_dpm_force_performance_level"),
+        power_profile: read("pp_power_profile_mode")
+            .as_deref()
+            .and_then(active_power_profile),
+        core_clock_levels: parse_clock_levels(&read("pp_dpm_sclk").unwrap_or_default()),
+        memory_clock_levels: parse_clock_levels(&read("pp_dpm_mclk").unwrap_or_default()),
+        // i915/xe expose the actual and maximum GT frequency on the card itself.
+        current_freq_mhz: freq("gt_act_freq_mhz").or_else(|| freq("gt_cur_freq_mhz")),
+        max_freq_mhz: freq("gt_max_freq_mhz"),
+        connectors: drm_connectors(&name, redact_serials),
+        pci_slot,
+        pci_description,
+        name,
+    }
+}
+
+fn parse_glxinfo(output: &str) -> OpenGlInfo {
+    let field = |label: &str| {
+        output.lines().find_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            (name.trim() == label).then(|| value.trim().to_string())
+        })
+    };
+    OpenGlInfo {
+        vendor: field("OpenGL vendor string"),
+        renderer: field("OpenGL renderer string"),
+        version: field("OpenGL version string"),
+        core_profile_version: field("OpenGL core profile version string"),
+        direct_rendering: field("direct rendering").map(|v| v.starts_with("Yes")),
+    }
+}
+
+// "GPU0:" headers followed by tab-indented "key = value" lines.
+fn parse_vulkaninfo_summary(output: &str) -> Vec<VulkanDeviceInfo> {
+    let mut devices: Vec<VulkanDeviceInfo> = Vec::new();
+    let mut in_device = false;
+    for line in output.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("GPU") && trimmed.ends_with(':') {
+            in_device = true;
+            devices.push(VulkanDeviceInfo {
+                name: String::new(),
+                device_type: None,
+                api_version: None,
+                driver_name: None,
+                driver_info: None,
+                vendor_id: None,
+                device_id: None,
+            });
+            continue;
+        }
+        if !line.starts_with(char::is_whitespace) {
+            in_device = false;
+            continue;
+        }
+        let (Some(device), true) = (devices.last_mut(), in_device) else {
+            continue;
+        };
+        let Some((key, value)) = trimmed.split_once('=') else {
+            continue;
+        };
+        let value = value.trim().to_string();
+        match key.trim() {
+            "deviceName" => device.name = value,
+            "deviceType" => {
+                device.device_type = Some(
+                    value
+                        .trim_start_matches("PHYSICAL_DEVICE_TYPE_")
+                        .to_ascii_lowercase(),
+                )
+            }
+            "apiVersion" => device.api_version = Some(value),
+            "driverName" => device.driver_name = Some(value),
+            "driverInfo" => device.driver_info = Some(value),
+            "vendorID" => device.vendor_id = Some(value),
+            "deviceID" => device.device_id = Some(value),
+            _ => {}
+        }
+    }
+    devices
+}
+
+pub fn gpu_info(lspci: &[PciBusInfo], redact_serials: bool) -> GpuInfo {
+    let cards = list_dir_sorted(DRM_DIR)
+        .into_iter()
+        .filter_map(|path| {
+            let name = path.file_name()?.to_string_lossy().into_owned();
+            is_card_name(&name).then(|| gpu_card(&path, name, lspci, redact_serials))
+        })
+        .collect();
+    GpuInfo {
+        cards,
+        opengl: run_command_optional("glxinfo", &["-B"]).map(|out| parse_glxinfo(&out)),
+        vulkan: run_command_optional("vulkaninfo", &["--summary"])
+            .map(|out| parse_vulkaninfo_summary(&out))
+            .unwrap_or_default(),
+    }
+}
@@ -0,0 +1,435 @@
+use std::collections::BTreeSet;
+use std::fs;
+
+use serde::Serialize;
+
+use crate::shell_config::ShellVars;
+use crate::utils::{file_mtime_secs, list_dir_sorted, path_string, run_command_with_input};
+
+const CPIO_HEADER_LEN: usize = 110;
+const CPIO_TRAILER: &str = "TRAILER!!!";
+
+#[derive(Serialize)]
+pub struct InitramfsImageInfo {
+    path: String,
+    size: u64,
+    modified: Option<u64>,
+    compression: String,
+    early_cpio: bool,
+    stale: Option<bool>,
+    contents: Option<InitramfsContentsInfo>,
+}
+
+#[derive(Serialize)]
+pub struct InitramfsContentsInfo {
+    uncompressed_size: u64,
+    file_count: usize,
+    kernel_modules: Vec<InitramfsFileInfo>,
+    binaries: Vec<InitramfsFileInfo>,
+    runtime_hooks: Vec<InitramfsFileInfo>,
+    build_hooks: Option<Vec<String>>,
+    hooks_match_config: Option<bool>,
+    missing_modules: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct InitramfsFileInfo {
+    name: String,
+    path: String,
+    size: u64,
+}
+
+struct ImageConfig<'a> {
+    modules: &'a [String],
+    hooks: Option<&'a [String]>,
+}
+
+struct CpioEntry {
+    name: String,
+    mode: u32,
+    size: u64,
+    data: Option<Vec<u8>>,
+}
+
+fn cpio_hex_field(header: &[u8], index: usize) -> Option<u64> {
+    let start = 6 + index * 8;
+    let field = std::str::from_utf8(header.get(start..start + 8)?).ok()?;
+    u64::from_str_radix(field, 16).ok()
+}
+
+fn align4(value: usize) -> usize {
+    (value + 3) & !3
+}
+
+fn parse_cpio(data: &[u8], entries: &mut Vec<CpioEntry>) -> usize {
+    let mut offset = 0usize;
+    while let Some(header) = data.get(offset..offset + CPIO_HEADER_LEN) {
+        if !header.starts_with(b"070701") && !header.starts_with(b"070702") {
+            break;
+        }
+        let (Some(mode), Some(size), Some(name_size)) = (
+            cpio_hex_field(header, 1),
+            cpio_hex_field(header, 6),
+            cpio_hex_field(header, 11),
+        ) else {
+            break;
+        };
+        let name_start = offset + CPIO_HEADER_LEN;
+        let name_end = name_start + name_size as usize;
+        let Some(name_bytes) = data.get(name_start..name_end) else {
+            break;
+        };
+        let name = String::from_utf8_lossy(name_bytes)
+            .trim_end_matches('\0')
+            .to_string();
+        let data_start = align4(name_end);
+        let data_end = data_start + size as usize;
+        offset = align4(data_end);
+        if name == CPIO_TRAILER {
+            return offset.min(data.len());
+        }
+        let keep_data = name == "buildconfig";
+        entries.push(CpioEntry {
+            data: keep_data
+                .then(|| data.get(data_start..data_end).map(<[u8]>::to_vec))
+                .flatten(),
+            name,
+            mode: mode as u32,
+            size,
+        });
+    }
+    offset.min(data.len())
+}
+
+fn parse_cpio_segments(mut data: &[u8], entries: &mut Vec<CpioEntry>) -> usize {
+    let mut consumed = 0usize;
+    while data.starts_with(b"07070") {
+        let used = parse_cpio(data, entries);
+        if used == 0 {
+            break;
+        }
+        let padding = data[used..].iter().take_while(|b| **b == 0).count();
+        consumed += used + padding;
+        data = &data[used + padding..];
+    }
+    consumed
+}
+
+fn detect_compression(data: &[u8]) -> &'static str {
+    if data.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+        "zstd"
+    } else if data.starts_with(&[0x1f, 0x8b]) {
+        "gzip"
+    } else if data.starts_with(&[0xfd, b'7', b'z', b'X', b'Z', 0x00]) {
+        "xz"
+    } else if data.starts_with(&[0x02, 0x21, 0x4c, 0x18])
+        || data.starts_with(&[0x04, 0x22, 0x4d, 0x18])
+    {
+        "lz4"
+    } else if data.starts_with(b"BZh") {
+        "bzip2"
+    } else if data.starts_with(&[0x89, b'L', b'Z', b'O']) {
+        "lzop"
+    } else if data.starts_with(&[0x5d, 0x00, 0x00]) {
+        "lzma"
+    } else if data.starts_with(b"07070") {
+        "none"
+    } else {
+        "unknown"
+    }
+}
+
+fn decompress(compression: &str, data: &[u8]) -> Option<Vec<u8>> {
+    let (cmd, args): (&str, &[&str]) = match compression {
+        "zstd" => ("zstd", &["-dcq"]),
+        "gzip" => ("gzip", &["-dc"]),
+        "xz" => ("xz", &["-dc"]),
+        "lzma" => ("xz", &["-dc", "--format=lzma"]),
+        "lz4" => ("lz4", &["-dc"]),
+        "bzip2" => ("bzip2", &["-dc"]),
+        "lzop" => ("lzop", &["-dc"]),
+        _ => return None,
+    };
+    run_command_with_input(cmd, args, data)
+}
+
+fn file_name(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+fn module_name(file: &str) -> Option<&str> {
+    file.find(".ko").map(|pos| &file[..pos])
+}
+
+fn normalize_module(name: &str) -> String {
+    name.replace('-', "_")
+}
+
+fn is_regular_file(entry: &CpioEntry) -> bool {
+    entry.mode & 0o170000 == 0o100000
+}
+
+fn is_binary_path(path: &str) -> bool {
+    ["bin/", "sbin/", "usr/bin/", "usr/sbin/"]
+        .iter()
+        .any(|prefix| path.starts_with(prefix))
+}
+
+fn summarize_contents(
+    entries: &[CpioEntry],
+    uncompressed_size: u64,
+    config: &ImageConfig,
+) -> InitramfsContentsInfo {
+    let file_info = |entry: &CpioEntry, name: &str| InitramfsFileInfo {
+        name: name.to_string(),
+        path: entry.name.clone(),
+        size: entry.size,
+    };
+    let mut kernel_modules = Vec::new();
+    let mut binaries = Vec::new();
+    let mut runtime_hooks = Vec::new();
+    let mut build_hooks = None;
+    for entry in entries {
+        let path = entry.name.trim_start_matches("./").trim_start_matches('/');
+        if path == "buildconfig" {
+            if let Some(data) = &entry.data {
+                let mut vars = ShellVars::default();
+                vars.parse(&String::from_utf8_lossy(data));
+                build_hooks = Some(vars.array("HOOKS"));
+            }
+            continue;
+        }
+        if !is_regular_file(entry) {
+            continue;
+        }
+        let name = file_name(path);
+        if path.contains("lib/modules/") {
+            if let Some(module) = module_name(name) {
+                kernel_modules.push(file_info(entry, module));
+            }
+        } else if let Some(hook) = path.strip_prefix("hooks/") {
+            runtime_hooks.push(file_info(entry, hook));
+        } else if is_binary_path(path) {
+            binaries.push(file_info(entry, name));
+        }
+    }
+
+    let included: BTreeSet<String> = kernel_modules
+        .iter()
+        .map(|module| normalize_module(&module.name))
+        .collect();
+    let missing_modules = config
+        .modules
+        .iter()
+        .filter(|module| !included.contains(&normalize_module(module)))
+        .cloned()
+        .collect();
+    let hooks_match_config = match (&build_hooks, config.hooks) {
+        (Some(built), Some(configured)) => Some(built.as_slice() == configured),
+        _ => None,
+    };
+
+    InitramfsContentsInfo {
+        uncompressed_size,
+        file_count: entries.len(),
+        kernel_modules,
+        binaries,
+        runtime_hooks,
+        build_hooks,
+        hooks_match_config,
+        missing_modules,
+    }
+}
+
+fn inspect_image(
+    path: &str,
+    config_mtime: Option<u64>,
+    config: &ImageConfig,
+) -> Option<InitramfsImageInfo> {
+    let bytes = fs::read(path).ok()?;
+    let modified = file_mtime_secs(path);
+    let mut entries = Vec::new();
+    let early_len = parse_cpio_segments(&bytes, &mut entries);
+    let rest = &bytes[early_len..];
+    let (compression, early_cpio, uncompressed) = if rest.is_empty() {
+        ("none", false, Some(early_len))
+    } else {
+        let compression = detect_compression(rest);
+        let decompressed = decompress(compression, rest);
+        let uncompressed = decompressed.map(|data| {
+            parse_cpio_segments(&data, &mut entries);
+            early_len + data.len()
+        });
+        (compression, early_len > 0, uncompressed)
+    };
+    let stale = match (modified, config_mtime) {
+        (Some(image), Some(config)) => Some(image < config),
+        _ => None,
+    };
+    Some(InitramfsImageInfo {
+        path: path.to_string(),
+        size: bytes.len() as u64,
+        modified,
+        compression: compression.to_string(),
+        early_cpio,
+        stale,
+        contents: uncompressed.map(|size| summarize_contents(&entries, size as u64, config)),
+    })
+}
+
+fn is_initramfs_name(name: &str) -> bool {
+    let looks_like_image =
+        name.starts_with("initramfs") || name.starts_with("initrd") || name.starts_with("booster");
+    looks_like_image && !name.ends_with(".efi")
+}
+
+fn initramfs_image_paths(extra_paths: &[String]) -> Vec<String> {
+    let mut paths: BTreeSet<String> = list_dir_sorted("/boot")
+        .into_iter()
+        .filter(|path| path.is_file())
+        .filter(|path| {
+            path.file_name()
+                .is_some_and(|name| is_initramfs_name(&name.to_string_lossy()))
+        })
+        .map(path_string)
+        .collect();
+    paths.extend(
+        extra_paths
+            .iter()
+            .filter(|path| fs::metadata(path).is_ok_and(|meta| meta.is_file()))
+            .cloned(),
+    );
+    paths.into_iter().collect()
+}
+
+pub fn initramfs_image_info(
+    config_files: &[String],
+    modules: &[String],
+    hooks: Option<&[String]>,
+    extra_paths: &[String],
+) -> Vec<InitramfsImageInfo> {
+    let config = ImageConfig { modules, hooks };
+    let config_mtime = config_files.iter().filter_map(file_mtime_secs).max();
+    initramfs_image_paths(extra_paths)
+        .iter()
+        .filter_map(|path| inspect_image(path, config_mtime, &config))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REGULAR: u32 = 0o100644;
+    const DIRECTORY: u32 = 0o040755;
+
+    fn cpio_entry(archive: &mut Vec<u8>, name: &str, mode: u32, data: &[u8]) {
+        let mut header = String::from("070701");
+        let name_size = name.len() + 1;
+        for value in [
+            0,
+            mode as usize,
+            0,
+            0,
+            1,
+            0,
+            data.len(),
+            0,
+            0,
+            0,
+            0,
+            name_size,
+            0,
+        ] {
+            header.push_str(&format!("{value:08X}"));
+        }
+        archive.extend_from_slice(header.as_bytes());
+        archive.extend_from_slice(name.as_bytes());
+        archive.push(0);
+        archive.resize(align4(archive.len()), 0);
+        archive.extend_from_slice(data);
+        archive.resize(align4(archive.len()), 0);
+    }
+
+    // An uncompressed early microcode archive, zero padding, then the main archive.
+    fn fixture() -> Vec<u8> {
+        let mut archive = Vec::new();
+        cpio_entry(&mut archive, "kernel", DIRECTORY, b"");
+        cpio_entry(
+            &mut archive,
+            "kernel/x86/microcode/GenuineIntel.bin",
+            REGULAR,
+            b"ucode",
+        );
+        cpio_entry(&mut archive, CPIO_TRAILER, 0, b"");
+        archive.resize(512, 0);
+        cpio_entry(&mut archive, "buildconfig", REGULAR, b"HOOKS=(base udev)\n");
+        cpio_entry(&mut archive, "hooks/udev", REGULAR, b"run_hook() {\n}\n");
+        cpio_entry(
+            &mut archive,
+            "usr/lib/modules/6.9.1-arch1-1/kernel/fs/ext4/ext4.ko.zst",
+            REGULAR,
+            &[0; 37],
+        );
+        cpio_entry(&mut archive, "usr/bin/busybox", 0o100755, &[0; 10]);
+        cpio_entry(&mut archive, "usr/lib/modules", DIRECTORY, b"");
+        cpio_entry(&mut archive, CPIO_TRAILER, 0, b"");
+        archive
+    }
+
+    #[test]
+    fn parses_concatenated_segments() {
+        let data = fixture();
+        let mut entries = Vec::new();
+        let consumed = parse_cpio_segments(&data, &mut entries);
+        assert_eq!(consumed, data.len());
+        let names: Vec<&str> = entries.iter().map(|entry| entry.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "kernel",
+                "kernel/x86/microcode/GenuineIntel.bin",
+                "buildconfig",
+                "hooks/udev",
+                "usr/lib/modules/6.9.1-arch1-1/kernel/fs/ext4/ext4.ko.zst",
+                "usr/bin/busybox",
+                "usr/lib/modules",
+            ]
+        );
+        assert_eq!(entries[1].size, 5);
+        assert!(entries[2].data.is_some());
+        assert!(entries[3].data.is_none());
+    }
+
+    #[test]
+    fn stops_at_truncated_header() {
+        let data = fixture();
+        let mut entries = Vec::new();
+        parse_cpio(&data[..200], &mut entries);
+        assert_eq!(entries.len(), 1);
+    }
+
+    #[test]
+    fn summarizes_contents() {
+        let data = fixture();
+        let mut entries = Vec::new();
+        parse_cpio_segments(&data, &mut entries);
+        let modules = ["ext4".to_string(), "crc32c-intel".to_string()];
+        let hooks = ["base".to_string(), "udev".to_string()];
+        let config = ImageConfig {
+            modules: &modules,
+            hooks: Some(&hooks),
+        };
+        let contents = summarize_contents(&entries, data.len() as u64, &config);
+        assert_eq!(contents.file_count, 7);
+        assert_eq!(contents.kernel_modules.len(), 1);
+        assert_eq!(contents.kernel_modules[0].name, "ext4");
+        assert_eq!(contents.kernel_modules[0].size, 37);
+        assert_eq!(contents.binaries[0].name, "busybox");
+        assert_eq!(contents.runtime_hooks[0].name, "udev");
+        assert_eq!(contents.runtime_hooks[0].size, 15);
+        assert_eq!(contents.build_hooks, Some(hooks.to_vec()));
+        assert_eq!(contents.hooks_match_config, Some(true));
+        assert_eq!(contents.missing_modules, ["crc32c-intel"]);
+    }
+}
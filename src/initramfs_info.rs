@@ -0,0 +1,123 @@
+use serde::Serialize;
+
+use crate::booster_info::{booster_info, BoosterInfo};
+use crate::dracut_info::{dracut_info, DracutInfo};
+use crate::initramfs_image_info::{initramfs_image_info, InitramfsImageInfo};
+use crate::mkinitcpio_info::{mkinitcpio_info, MkinitcpioInfo};
+use crate::utils::{find_in_path, list_dir_sorted};
+
+#[derive(Serialize)]
+pub struct InitramfsInfo {
+    generator: Option<String>,
+    installed_generators: Vec<String>,
+    config_files: Vec<String>,
+    modules: Vec<String>,
+    hooks: Vec<String>,
+    compression: Option<String>,
+    mkinitcpio: Option<MkinitcpioInfo>,
+    dracut: Option<DracutInfo>,
+    booster: Option<BoosterInfo>,
+    images: Vec<InitramfsImageInfo>,
+}
+
+fn has_booster_images() -> bool {
+    list_dir_sorted("/boot").iter().any(|path| {
+        path.file_name()
+            .is_some_and(|name| name.to_string_lossy().starts_with("booster"))
+    })
+}
+
+fn select_generator(installed: &[String], mkinitcpio: &MkinitcpioInfo) -> Option<String> {
+    let is_installed = |name: &str| installed.iter().any(|g| g == name);
+    if installed.len() <= 1 {
+        return installed.first().cloned();
+    }
+    // Several generators can be installed side by side; prefer the one whose
+    // images are actually present.
+    if is_installed("booster") && has_booster_images() {
+        return Some("booster".to_string());
+    }
+    let mkinitcpio_images = mkinitcpio
+        .image_paths()
+        .iter()
+        .any(|path| std::path::Path::new(path).is_file());
+    if is_installed("mkinitcpio") && mkinitcpio_images {
+        return Some("mkinitcpio".to_string());
+    }
+    ["dracut", "mkinitcpio", "booster"]
+        .into_iter()
+        .find(|name| is_installed(name))
+        .map(String::from)
+}
+
+pub fn initramfs_info() -> InitramfsInfo {
+    let mkinitcpio = mkinitcpio_info();
+    let dracut = dracut_info();
+    let booster = booster_info();
+
+    let installed_generators: Vec<String> = [
+        ("mkinitcpio", mkinitcpio.is_configured()),
+        ("dracut", dracut.is_some()),
+        ("booster", booster.is_some()),
+    ]
+    .into_iter()
+    .filter(|(name, configured)| *configured || find_in_path(name).is_some())
+    .map(|(name, _)| name.to_string())
+    .collect();
+    let generator = select_generator(&installed_generators, &mkinitcpio);
+
+    let mut config_files = Vec::new();
+    let mut modules = Vec::new();
+    let mut hooks = Vec::new();
+    let mut compression = None;
+    let mut image_hooks = None;
+    match generator.as_deref() {
+        Some("mkinitcpio") => {
+            config_files = mkinitcpio.config_files().to_vec();
+            modules = mkinitcpio
+                .modules()
+                .iter()
+                .map(|module| module.trim_end_matches('?').to_string())
+                .collect();
+            hooks = mkinitcpio.hooks().to_vec();
+            compression = mkinitcpio.compression().map(String::from);
+            image_hooks = Some(hooks.as_slice());
+        }
+        Some("dracut") => {
+            if let Some(dracut) = &dracut {
+                config_files = dracut.config_files().to_vec();
+                modules = dracut.drivers();
+                hooks = dracut.enabled_modules();
+                compression = dracut.compression().map(String::from);
+            }
+        }
+        Some("booster") => {
+            if let Some(booster) = &booster {
+                config_files = vec![booster.config_file().to_string()];
+                modules = booster.included_modules();
+                hooks = booster.features();
+                compression = booster.compression().map(String::from);
+            }
+        }
+        _ => {}
+    }
+    let images = initramfs_image_info(
+        &config_files,
+        &modules,
+        image_hooks,
+        &mkinitcpio.image_paths(),
+    );
+
+    InitramfsInfo {
+        generator,
+        installed_generators,
+        config_files,
+        modules,
+        hooks,
+        compression,
+        mkinitcpio: mkinitcpio.is_configured().then_some(mkinitcpio),
+        dracut,
+        booster,
+        images,
+    }
+}
@@ -0,0 +1,389 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::utils::{read_file_optional, read_file_string, run_command_optional};
+
+const LOGIN_DEFS: &str = "/etc/login.defs";
+const SHELLS: &str = "/etc/shells";
+const UTMP: &str = "/run/utmp";
+const WTMP: &str = "/var/log/wtmp";
+const DEFAULT_UID_MIN: u32 = 1000;
+const DEFAULT_UID_MAX: u32 = 60000;
+const RECENT_LOGINS: usize = 20;
+
+// struct utmp as laid out by glibc on Linux (identical on 32- and 64-bit, since
+// ut_tv uses 32-bit fields there).
+const UTMP_RECORD_LEN: usize = 384;
+const UT_LINESIZE: usize = 32;
+const UT_NAMESIZE: usize = 32;
+const UT_HOSTSIZE: usize = 256;
+const RUN_LVL: i16 = 1;
+const BOOT_TIME: i16 = 2;
+const USER_PROCESS: i16 = 7;
+const DEAD_PROCESS: i16 = 8;
+
+#[derive(Serialize)]
+pub struct LocalUsersInfo {
+    uid_min: u32,
+    uid_max: u32,
+    users: Vec<LocalUserInfo>,
+    sessions_source: Option<String>,
+    sessions: Vec<LoginSessionInfo>,
+    recent_logins: Vec<LoginRecordInfo>,
+}
+
+#[derive(Serialize)]
+pub struct LocalUserInfo {
+    username: String,
+    uid: u32,
+    gid: u32,
+    full_name: Option<String>,
+    home_directory: String,
+    home_exists: bool,
+    login_shell: String,
+    shell_listed: bool,
+    can_login: bool,
+}
+
+#[derive(Serialize)]
+pub struct LoginSessionInfo {
+    id: Option<String>,
+    user: String,
+    uid: Option<u32>,
+    seat: Option<String>,
+    tty: Option<String>,
+    remote_host: Option<String>,
+    service: Option<String>,
+    session_type: Option<String>,
+    class: Option<String>,
+    state: Option<String>,
+    since: Option<u64>,
+}
+
+#[derive(Serialize)]
+pub struct LoginRecordInfo {
+    user: String,
+    tty: String,
+    host: Option<String>,
+    login: u64,
+    logout: Option<u64>,
+    status: String,
+}
+
+struct UtmpRecord {
+    kind: i16,
+    line: String,
+    user: String,
+    host: String,
+    seconds: u64,
+}
+
+// login.defs is "KEY value" with '#' comments.
+fn login_defs_value(text: &str, key: &str) -> Option<u32> {
+    text.lines().find_map(|line| {
+        let mut parts = line.split_whitespace();
+        if parts.next()? != key {
+            return None;
+        }
+        parts.next()?.parse().ok()
+    })
+}
+
+fn local_users(uid_min: u32, uid_max: u32) -> Vec<LocalUserInfo> {
+    let shells: Vec<String> = read_file_string(SHELLS)
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(String::from)
+        .collect();
+    let passwd = run_command_optional("getent", &["passwd"])
+        .or_else(|| read_file_optional("/etc/passwd"))
+        .unwrap_or_default();
+    let mut users: Vec<LocalUserInfo> = passwd
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split(':').collect();
+            if fields.len() < 7 {
+                return None;
+            }
+            let uid: u32 = fields[2].parse().ok()?;
+            if uid < uid_min || uid > uid_max {
+                return None;
+            }
+            let full_name = fields[4]
+                .split(',')
+                .next()
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .map(String::from);
+            let login_shell = fields[6].to_string();
+            let no_login = login_shell.ends_with("/nologin") || login_shell.ends_with("/false");
+            Some(LocalUserInfo {
+                username: fields[0].to_string(),
+                uid,
+                gid: fields[3].parse().unwrap_or_default(),
+                full_name,
+                home_directory: fields[5].to_string(),
+                home_exists: Path::new(fields[5]).is_dir(),
+                shell_listed: shells.contains(&login_shell),
+                can_login: !login_shell.is_empty() && !no_login,
+                login_shell,
+            })
+        })
+        .collect();
+    users.sort_by_key(|user| user.uid);
+    users
+}
+
+fn c_string(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).trim().to_string()
+}
+
+fn parse_utmp(data: &[u8]) -> Vec<UtmpRecord> {
+    data.chunks_exact(UTMP_RECORD_LEN)
+        .map(|record| {
+            let i32_at = |offset: usize| {
+                i32::from_le_bytes([
+                    record[offset],
+                    record[offset + 1],
+                    record[offset + 2],
+                    record[offset + 3],
+                ])
+            };
+            let line_start = 8;
+            let user_start = line_start + UT_LINESIZE + 4;
+            let host_start = user_start + UT_NAMESIZE;
+            // ut_exit (4 bytes) and ut_session (4 bytes) precede ut_tv.
+            let tv_start = host_start + UT_HOSTSIZE + 8;
+            UtmpRecord {
+                kind: i16::from_le_bytes([record[0], record[1]]),
+                line: c_string(&record[line_start..line_start + UT_LINESIZE]),
+                user: c_string(&record[user_start..user_start + UT_NAMESIZE]),
+                host: c_string(&record[host_start..host_start + UT_HOSTSIZE]),
+                seconds: u64::try_from(i32_at(tv_start)).unwrap_or_default(),
+            }
+        })
+        .collect()
+}
+
+fn read_utmp(path: &str) -> Option<Vec<UtmpRecord>> {
+    fs::read(path).ok().map(|data| parse_utmp(&data))
+}
+
+fn boot_time_secs() -> Option<u64> {
+    read_file_string("/proc/stat")
+        .lines()
+        .find_map(|line| line.strip_prefix("btime ")?.trim().parse().ok())
+}
+
+fn loginctl_sessions() -> Option<Vec<LoginSessionInfo>> {
+    let list = run_command_optional("loginctl", &["list-sessions", "--no-legend"])?;
+    let boot_time = boot_time_secs();
+    let sessions = list
+        .lines()
+        .filter_map(|line| line.split_whitespace().next())
+        .filter_map(|id| {
+            let output = run_command_optional(
+                "loginctl",
+                &[
+                    "show-session",
+                    id,
+                    "--property=Id,Name,User,Seat,TTY,RemoteHost,Service,Type,Class,State,TimestampMonotonic",
+                ],
+            )?;
+            let props: HashMap<&str, &str> = output
+                .lines()
+                .filter_map(|line| line.split_once('='))
+                .filter(|(_, value)| !value.is_empty())
+                .collect();
+            let prop = |key: &str| props.get(key).map(|value| value.to_string());
+            // The monotonic timestamp (µs since boot) is unambiguous, unlike the
+            // localized Timestamp property.
+            let since = props
+                .get("TimestampMonotonic")
+                .and_then(|value| value.parse::<u64>().ok())
+                .filter(|micros| *micros > 0)
+                .and_then(|micros| Some(boot_time? + micros / 1_000_000));
+            Some(LoginSessionInfo {
+                id: prop("Id").or_else(|| Some(id.to_string())),
+                user: prop("Name").unwrap_or_default(),
+                uid: props.get("User").and_then(|uid| uid.parse().ok()),
+                seat: prop("Seat"),
+                tty: prop("TTY"),
+                remote_host: prop("RemoteHost"),
+                service: prop("Service"),
+                session_type: prop("Type"),
+                class: prop("Class"),
+                state: prop("State"),
+                since,
+            })
+        })
+        .collect();
+    Some(sessions)
+}
+
+fn utmp_sessions(records: &[UtmpRecord]) -> Vec<LoginSessionInfo> {
+    records
+        .iter()
+        .filter(|record| record.kind == USER_PROCESS && !record.user.is_empty())
+        .map(|record| LoginSessionInfo {
+            id: None,
+            user: record.user.clone(),
+            uid: None,
+            seat: None,
+            tty: Some(record.line.clone()).filter(|line| !line.is_empty()),
+            remote_host: Some(record.host.clone()).filter(|host| !host.is_empty()),
+            service: None,
+            session_type: None,
+            class: None,
+            state: None,
+            since: Some(record.seconds),
+        })
+        .collect()
+}
+
+fn close_logins(
+    logins: &mut [LoginRecordInfo],
+    open: &mut HashMap<String, usize>,
+    seconds: u64,
+    status: &str,
+) {
+    for (_, index) in open.drain() {
+        logins[index].logout = Some(seconds);
+        logins[index].status = status.to_string();
+    }
+}
+
+// Pairs wtmp logins with the DEAD_PROCESS record for the same tty, the way
+// `last` does; a reboot or shutdown closes everything still open.
+fn recent_logins(records: &[UtmpRecord], limit: usize) -> Vec<LoginRecordInfo> {
+    let mut logins: Vec<LoginRecordInfo> = Vec::new();
+    let mut open: HashMap<String, usize> = HashMap::new();
+    for record in records {
+        match record.kind {
+            USER_PROCESS if !record.user.is_empty() => {
+                open.insert(record.line.clone(), logins.len());
+                logins.push(LoginRecordInfo {
+                    user: record.user.clone(),
+                    tty: record.line.clone(),
+                    host: Some(record.host.clone()).filter(|host| !host.is_empty()),
+                    login: record.seconds,
+                    logout: None,
+                    status: "logged_in".to_string(),
+                });
+            }
+            DEAD_PROCESS => {
+                if let Some(index) = open.remove(&record.line) {
+                    logins[index].logout = Some(record.seconds);
+                    logins[index].status = "logged_out".to_string();
+                }
+            }
+            BOOT_TIME => close_logins(&mut logins, &mut open, record.seconds, "crash"),
+            RUN_LVL if record.user == "shutdown" => {
+                close_logins(&mut logins, &mut open, record.seconds, "down")
+            }
+            _ => {}
+        }
+    }
+    logins.drain(..logins.len().saturating_sub(limit));
+    logins.reverse();
+    logins
+}
+
+pub fn local_users_info() -> LocalUsersInfo {
+    let login_defs = read_file_string(LOGIN_DEFS);
+    let uid_min = login_defs_value(&login_defs, "UID_MIN").unwrap_or(DEFAULT_UID_MIN);
+    let uid_max = login_defs_value(&login_defs, "UID_MAX").unwrap_or(DEFAULT_UID_MAX);
+    let (sessions_source, sessions) = match loginctl_sessions() {
+        Some(sessions) => (Some("loginctl".to_string()), sessions),
+        None => match read_utmp(UTMP) {
+            Some(records) => (Some(UTMP.to_string()), utmp_sessions(&records)),
+            None => (None, Vec::new()),
+        },
+    };
+    let recent_logins = read_utmp(WTMP)
+        .map(|records| recent_logins(&records, RECENT_LOGINS))
+        .unwrap_or_default();
+    LocalUsersInfo {
+        uid_min,
+        uid_max,
+        users: local_users(uid_min, uid_max),
+        sessions_source,
+        sessions,
+        recent_logins,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(kind: i16, line: &str, user: &str, host: &str, seconds: i32) -> Vec<u8> {
+        let mut record = vec![0u8; UTMP_RECORD_LEN];
+        record[0..2].copy_from_slice(&kind.to_le_bytes());
+        record[8..8 + line.len()].copy_from_slice(line.as_bytes());
+        record[44..44 + user.len()].copy_from_slice(user.as_bytes());
+        record[76..76 + host.len()].copy_from_slice(host.as_bytes());
+        record[340..344].copy_from_slice(&seconds.to_le_bytes());
+        record
+    }
+
+    #[test]
+    fn parses_utmp_record_offsets() {
+        let data = record(USER_PROCESS, "pts/0", "alice", "192.0.2.7", 1_700_000_000);
+        let records = parse_utmp(&data);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].kind, USER_PROCESS);
+        assert_eq!(records[0].line, "pts/0");
+        assert_eq!(records[0].user, "alice");
+        assert_eq!(records[0].host, "192.0.2.7");
+        assert_eq!(records[0].seconds, 1_700_000_000);
+        // A truncated trailing record is ignored.
+        assert_eq!(parse_utmp(&data[..UTMP_RECORD_LEN - 1]).len(), 0);
+    }
+
+    #[test]
+    fn pairs_logins_like_last() {
+        let data = [
+            record(BOOT_TIME, "~", "reboot", "", 100),
+            record(USER_PROCESS, "tty1", "alice", "", 110),
+            record(USER_PROCESS, "pts/0", "bob", "example.org", 120),
+            record(DEAD_PROCESS, "pts/0", "", "", 130),
+            record(USER_PROCESS, "pts/1", "carol", "", 140),
+            record(RUN_LVL, "~", "shutdown", "", 150),
+            record(BOOT_TIME, "~", "reboot", "", 200),
+            record(USER_PROCESS, "tty1", "alice", "", 210),
+        ]
+        .concat();
+        let logins = recent_logins(&parse_utmp(&data), 10);
+        let summary: Vec<(&str, u64, Option<u64>, &str)> = logins
+            .iter()
+            .map(|login| {
+                (
+                    login.user.as_str(),
+                    login.login,
+                    login.logout,
+                    login.status.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("alice", 210, None, "logged_in"),
+                ("carol", 140, Some(150), "down"),
+                ("bob", 120, Some(130), "logged_out"),
+                ("alice", 110, Some(150), "down"),
+            ]
+        );
+        assert_eq!(logins[2].host.as_deref(), Some("example.org"));
+
+        let limited = recent_logins(&parse_utmp(&data), 2);
+        assert_eq!(limited.len(), 2);
+        assert_eq!(limited[1].user, "carol");
+    }
+}
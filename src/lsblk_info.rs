@@ -1,7 +1,14 @@
+use std::collections::HashMap;
+use std::fs;
+use std::os::unix::fs::MetadataExt;
+use std::path::Path;
+
 use serde::Serialize;
 use serde_json::Value;
 
-use crate::utils::run_command_optional;
+use crate::utils::{
+    dev_maj_min, list_dir_sorted, read_file_optional, read_file_string, run_command_optional,
+};
 
 #[derive(Serialize)]
 pub struct BlockDeviceInfo {
@@ -28,6 +35,74 @@ pub struct BlockDeviceInfo {
     mountpoints: String,
 }
 
+impl BlockDeviceInfo {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn dev_type(&self) -> &str {
+        &self.dev_type
+    }
+
+    pub fn maj_min(&self) -> &str {
+        &self.maj_min
+    }
+}
+
+// Finds the lsblk entry for a device node, by path or else by device number, so
+// that symlinks such as /dev/vg/lv or /dev/md/name resolve to their entry.
+pub fn block_device_for_path<'a>(
+    path: &str,
+    devices: &'a [BlockDeviceInfo],
+) -> Option<&'a BlockDeviceInfo> {
+    if let Some(dev) = devices.iter().find(|dev| dev.path == path) {
+        return Some(dev);
+    }
+    let maj_min = dev_maj_min(fs::metadata(path).ok()?.rdev());
+    devices.iter().find(|dev| dev.maj_min == maj_min)
+}
+
+// Like block_device_spec_exists, but returns the matching entry.
+pub fn block_device_for_spec<'a>(
+    spec: &str,
+    devices: &'a [BlockDeviceInfo],
+) -> Option<&'a BlockDeviceInfo> {
+    let (key, value) = spec.split_once('=').unwrap_or(("", spec));
+    match key {
+        "UUID" => devices
+            .iter()
+            .find(|dev| dev.uuid.eq_ignore_ascii_case(value)),
+        "PARTUUID" => devices
+            .iter()
+            .find(|dev| dev.partuuid.eq_ignore_ascii_case(value)),
+        "LABEL" => devices.iter().find(|dev| dev.label == value),
+        "PARTLABEL" => block_device_for_path(&format!("/dev/disk/by-partlabel/{value}"), devices),
+        "" if spec.starts_with("/dev/") => block_device_for_path(spec, devices),
+        _ => None,
+    }
+}
+
+// Resolves fstab-style specs (UUID=, PARTUUID=, LABEL=, PARTLABEL=, /dev/...).
+// Returns None when the spec cannot be checked (e.g. pseudo filesystems).
+pub fn block_device_spec_exists(spec: &str, devices: &[BlockDeviceInfo]) -> Option<bool> {
+    let any = |matches: &dyn Fn(&BlockDeviceInfo) -> bool| devices.iter().any(matches);
+    let (key, value) = spec.split_once('=').unwrap_or(("", spec));
+    match key {
+        "UUID" => Some(any(&|dev| dev.uuid.eq_ignore_ascii_case(value))),
+        "PARTUUID" => Some(any(&|dev| dev.partuuid.eq_ignore_ascii_case(value))),
+        "LABEL" => Some(any(&|dev| dev.label == value)),
+        "PARTLABEL" => Some(Path::new("/dev/disk/by-partlabel").join(value).exists()),
+        "" if spec.starts_with("/dev/") => {
+            Some(any(&|dev| dev.path == spec) || Path::new(spec).exists())
+        }
+        _ => None,
+    }
+}
+
 pub fn lsblk_info() -> Vec<BlockDeviceInfo> {
     let output = match run_command_optional(
         "lsblk",
@@ -94,3 +169,117 @@ pub fn lsblk_info() -> Vec<BlockDeviceInfo> {
     }
     out
 }
+
+#[derive(Serialize)]
+pub struct BlockDeviceNode {
+    kernel_name: String,
+    name: String,
+    path: String,
+    maj_min: String,
+    dev_type: String,
+    size: String,
+    fstype: String,
+    label: String,
+    uuid: String,
+    mountpoints: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dm_name: Option<String>,
+    children: Vec<BlockDeviceNode>,
+}
+
+fn sysfs_dev_type(sys_path: &Path) -> String {
+    if sys_path.join("partition").is_file() {
+        return "part".to_string();
+    }
+    if let Some(uuid) = read_file_optional(sys_path.join("dm/uuid")) {
+        let kind = uuid.split('-').next().unwrap_or_default();
+        return match kind {
+            "CRYPT" => "crypt".to_string(),
+            "LVM" => "lvm".to_string(),
+            "" => "dm".to_string(),
+            other => other.to_ascii_lowercase(),
+        };
+    }
+    if let Some(level) = read_file_optional(sys_path.join("md/level")) {
+        return level;
+    }
+    "disk".to_string()
+}
+
+fn sysfs_children(name: &str, sys_path: &Path) -> Vec<String> {
+    let mut children: Vec<String> = list_dir_sorted(sys_path)
+        .into_iter()
+        .filter(|path| path.join("partition").is_file())
+        .filter_map(|path| Some(path.file_name()?.to_string_lossy().into_owned()))
+        .filter(|child| child.starts_with(name))
+        .collect();
+    children.extend(
+        list_dir_sorted(sys_path.join("holders"))
+            .into_iter()
+            .filter_map(|path| Some(path.file_name()?.to_string_lossy().into_owned())),
+    );
+    children
+}
+
+fn block_device_node(
+    kernel_name: &str,
+    by_maj_min: &HashMap<&str, &BlockDeviceInfo>,
+    ancestors: &mut Vec<String>,
+) -> BlockDeviceNode {
+    let sys_path = Path::new("/sys/class/block").join(kernel_name);
+    let maj_min = read_file_string(sys_path.join("dev"));
+    let device = by_maj_min.get(maj_min.as_str()).copied();
+    let field = |get: fn(&BlockDeviceInfo) -> &String| {
+        device.map(|dev| get(dev).clone()).unwrap_or_default()
+    };
+    let mut dev_type = field(|dev| &dev.dev_type);
+    if dev_type.is_empty() {
+        dev_type = sysfs_dev_type(&sys_path);
+    }
+    let mut path = field(|dev| &dev.path);
+    if path.is_empty() {
+        path = format!("/dev/{kernel_name}");
+    }
+
+    // Stacked devices can hold each other only in a DAG, but guard against
+    // cycles from odd sysfs layouts anyway.
+    let child_names: Vec<String> = sysfs_children(kernel_name, &sys_path)
+        .into_iter()
+        .filter(|child| child != kernel_name && !ancestors.contains(child))
+        .collect();
+    ancestors.push(kernel_name.to_string());
+    let children = child_names
+        .iter()
+        .map(|child| block_device_node(child, by_maj_min, ancestors))
+        .collect();
+    ancestors.pop();
+
+    BlockDeviceNode {
+        kernel_name: kernel_name.to_string(),
+        name: field(|dev| &dev.name),
+        path,
+        maj_min,
+        dev_type,
+        size: field(|dev| &dev.size),
+        fstype: field(|dev| &dev.fstype),
+        label: field(|dev| &dev.label),
+        uuid: field(|dev| &dev.uuid),
+        mountpoints: field(|dev| &dev.mountpoints),
+        dm_name: read_file_optional(sys_path.join("dm/name")),
+        children,
+    }
+}
+
+pub fn lsblk_tree(devices: &[BlockDeviceInfo]) -> Vec<BlockDeviceNode> {
+    let by_maj_min: HashMap<&str, &BlockDeviceInfo> = devices
+        .iter()
+        .map(|dev| (dev.maj_min.as_str(), dev))
+        .collect();
+    let mut ancestors = Vec::new();
+    list_dir_sorted("/sys/block")
+        .into_iter()
+        .filter(|path| list_dir_sorted(path.join("slaves")).is_empty())
+        .filter_map(|path| Some(path.file_name()?.to_string_lossy().into_owned()))
+        .map(|name| block_device_node(&name, &by_maj_min, &mut ancestors))
+        .collect()
+}
@@ -2,30 +2,64 @@ use serde::Serialize;
 use serde_json::ser::{PrettyFormatter, Serializer};
 use std::env;
 
+mod app_sources_info;
+mod booster_info;
+mod boot_info;
 mod cpu_info;
+mod disk_health_info;
+mod display_info;
 mod dmi_info;
+mod dracut_info;
+mod edid;
 mod env_info;
+mod filesystem_info;
+mod firmware_info;
+mod gpu_info;
+mod initramfs_image_info;
+mod initramfs_info;
+mod local_users_info;
 mod lsblk_info;
 mod lsmod_info;
 mod mkinitcpio_info;
+mod mounts_info;
+mod package_verify_info;
+mod packages_info;
+mod pacman_config_info;
 mod pacman_info;
+mod pacman_log_info;
 mod pci_info;
 mod proc_info;
+mod raid_lvm_info;
+mod sha256;
+mod shell_config;
+mod swap_info;
 mod uname_info;
 mod user_passwd_info;
 mod utils;
 mod x11_info;
 mod xdg_info;
 
+use crate::boot_info::{boot_info, BootInfo};
 use crate::cpu_info::{cpu_info, CpuInfo};
+use crate::disk_health_info::{disk_health_info, DiskHealthInfo};
+use crate::display_info::{display_info, DisplayInfo};
 use crate::dmi_info::{dmi_info, DmiInfo};
-use crate::env_info::{env_info, EnvInfo};
-use crate::lsblk_info::{lsblk_info, BlockDeviceInfo};
+use crate::env_info::{env_info, EnvCapture, EnvInfo};
+use crate::filesystem_info::{filesystem_info, FilesystemInfo, FilesystemThresholds};
+use crate::firmware_info::{firmware_info, FirmwareInfo};
+use crate::gpu_info::{gpu_info, GpuInfo};
+use crate::initramfs_info::{initramfs_info, InitramfsInfo};
+use crate::local_users_info::{local_users_info, LocalUsersInfo};
+use crate::lsblk_info::{lsblk_info, lsblk_tree, BlockDeviceInfo, BlockDeviceNode};
 use crate::lsmod_info::{lsmod_info, KernelModuleInfo};
-use crate::mkinitcpio_info::{mkinitcpio_info, MkinitcpioInfo};
+use crate::mounts_info::{mounts_info, MountsInfo};
+use crate::package_verify_info::{package_verify_info, PackageVerificationInfo};
+use crate::packages_info::{packages_info, PackagesInfo};
 use crate::pacman_info::{pacman_info, PacmanInfo};
 use crate::pci_info::{pci_info, PciBusInfo};
 use crate::proc_info::{proc_info, ProcInfo};
+use crate::raid_lvm_info::{raid_lvm_info, RaidLvmInfo};
+use crate::swap_info::{swap_info, SwapInfo};
 use crate::uname_info::{uname_info, UnameInfo};
 use crate::user_passwd_info::{user_passwd_info, UserPasswdInfo};
 use crate::x11_info::{x11_info, X11Info};
@@ -35,15 +69,30 @@ use crate::xdg_info::{xdg_info, XdgInfo};
 struct SystemInfo {
     uname: UnameInfo,
     user: UserPasswdInfo,
+    local_users: LocalUsersInfo,
     env: EnvInfo,
     dmi: DmiInfo,
+    firmware: FirmwareInfo,
     xdg: XdgInfo,
     cpu: CpuInfo,
     proc: ProcInfo,
-    mkinitcpio: MkinitcpioInfo,
+    initramfs: InitramfsInfo,
+    boot: BootInfo,
+    display: DisplayInfo,
     x11: X11Info,
+    gpu: GpuInfo,
     pacman: PacmanInfo,
+    packages: PackagesInfo,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    package_verification: Option<PackageVerificationInfo>,
     lsblk: Vec<BlockDeviceInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lsblk_tree: Option<Vec<BlockDeviceNode>>,
+    disk_health: Vec<DiskHealthInfo>,
+    mounts: MountsInfo,
+    swap: SwapInfo,
+    filesystems: FilesystemInfo,
+    raid_lvm: RaidLvmInfo,
     lspci: Vec<PciBusInfo>,
     lsmod: Vec<KernelModuleInfo>,
 }
@@ -51,6 +100,20 @@ struct SystemInfo {
 fn main() {
     let mut pretty = false;
     let mut indent = 4usize;
+    let mut with_lsblk_tree = false;
+    let mut redact_serials = false;
+    let mut fs_thresholds = FilesystemThresholds {
+        warn_percent: 90.0,
+        critical_percent: 95.0,
+    };
+    let mut pacman_transactions = 10usize;
+    let mut verify_packages = false;
+    let mut env_capture = EnvCapture {
+        all: false,
+        include: Vec::new(),
+        exclude: Vec::new(),
+        show_secrets: false,
+    };
     for arg in env::args().skip(1) {
         if arg == "--pretty" {
             pretty = true;
@@ -59,22 +122,88 @@ fn main() {
                 indent = parsed;
             }
             pretty = true;
+        } else if arg == "--lsblk-tree" {
+            with_lsblk_tree = true;
+        } else if arg == "--redact-serials" {
+            redact_serials = true;
+        } else if let Some(value) = arg.strip_prefix("--fs-warn=")
+            && let Ok(parsed) = value.parse::<f64>()
+            && (0.0..=100.0).contains(&parsed)
+        {
+            fs_thresholds.warn_percent = parsed;
+        } else if let Some(value) = arg.strip_prefix("--fs-critical=")
+            && let Ok(parsed) = value.parse::<f64>()
+            && (0.0..=100.0).contains(&parsed)
+        {
+            fs_thresholds.critical_percent = parsed;
+        } else if let Some(value) = arg.strip_prefix("--pacman-transactions=")
+            && let Ok(parsed) = value.parse::<usize>()
+        {
+            pacman_transactions = parsed;
+        } else if arg == "--verify-packages" {
+            verify_packages = true;
+        } else if arg == "--env=all" {
+            env_capture.all = true;
+        } else if let Some(value) = arg.strip_prefix("--env-vars=") {
+            env_capture
+                .include
+                .extend(value.split(',').filter(|name| !name.is_empty()).map(String::from));
+        } else if let Some(value) = arg.strip_prefix("--env-exclude=") {
+            env_capture
+                .exclude
+                .extend(value.split(',').filter(|name| !name.is_empty()).map(String::from));
+        } else if arg == "--env-show-secrets" {
+            env_capture.show_secrets = true;
         }
     }
 
+    // A warning level above the critical one could never be reported.
+    fs_thresholds.warn_percent = fs_thresholds
+        .warn_percent
+        .min(fs_thresholds.critical_percent);
+
+    let xdg = xdg_info();
+    let display = display_info(&xdg);
+    let proc = proc_info();
+    let boot = boot_info(&proc);
+    let lsblk = lsblk_info();
+    let lsblk_tree = with_lsblk_tree.then(|| lsblk_tree(&lsblk));
+    let disk_health = disk_health_info(&lsblk, redact_serials);
+    let mounts = mounts_info(&lsblk);
+    let swap = swap_info(&proc, &lsblk);
+    let filesystems = filesystem_info(&mounts, &fs_thresholds);
+    let raid_lvm = raid_lvm_info(&lsblk);
+    let pacman = pacman_info(pacman_transactions);
+    let packages = packages_info(&pacman);
+    let package_verification = verify_packages.then(package_verify_info);
+    let lspci = pci_info();
+    let gpu = gpu_info(&lspci, redact_serials);
     let info = SystemInfo {
         uname: uname_info(),
         user: user_passwd_info(),
-        env: env_info(),
+        local_users: local_users_info(),
+        env: env_info(&env_capture),
         dmi: dmi_info(),
-        xdg: xdg_info(),
+        firmware: firmware_info(),
+        xdg,
         cpu: cpu_info(),
-        proc: proc_info(),
-        mkinitcpio: mkinitcpio_info(),
-        x11: x11_info(),
-        pacman: pacman_info(),
-        lsblk: lsblk_info(),
-        lspci: pci_info(),
+        proc,
+        initramfs: initramfs_info(),
+        boot,
+        display,
+        x11: x11_info(redact_serials),
+        gpu,
+        pacman,
+        packages,
+        package_verification,
+        lsblk,
+        lsblk_tree,
+        disk_health,
+        mounts,
+        swap,
+        filesystems,
+        raid_lvm,
+        lspci,
         lsmod: lsmod_info(),
     };
     if pretty {
@@ -1,36 +1,142 @@
 use serde::Serialize;
 
-use crate::utils::run_command_optional;
+use crate::shell_config::ShellVars;
+use crate::utils::{list_dir_sorted, path_string, read_file_optional};
+
+const MKINITCPIO_CONF: &str = "/etc/mkinitcpio.conf";
+const MKINITCPIO_CONF_DIR: &str = "/etc/mkinitcpio.conf.d";
+const MKINITCPIO_PRESET_DIR: &str = "/etc/mkinitcpio.d";
 
 #[derive(Serialize)]
 pub struct MkinitcpioInfo {
+    config_files: Vec<String>,
     modules: Vec<String>,
+    binaries: Vec<String>,
+    files: Vec<String>,
     hooks: Vec<String>,
+    compression: Option<String>,
+    compression_options: Vec<String>,
+    modules_decompress: Option<String>,
+    presets: Vec<MkinitcpioPresetInfo>,
+}
+
+#[derive(Serialize)]
+pub struct MkinitcpioPresetInfo {
+    name: String,
+    path: String,
+    all_kver: Option<String>,
+    all_config: Option<String>,
+    images: Vec<MkinitcpioPresetImageInfo>,
+}
+
+#[derive(Serialize)]
+pub struct MkinitcpioPresetImageInfo {
+    preset: String,
+    kver: Option<String>,
+    config: String,
+    image: Option<String>,
+    uki: Option<String>,
+    options: Vec<String>,
 }
 
-fn parse_mkinitcpio_list(line: &str) -> Vec<String> {
-    let start = match line.find('(') {
-        Some(pos) => pos + 1,
-        None => return Vec::new(),
-    };
-    let end = match line.rfind(')') {
-        Some(pos) if pos > start => pos,
-        _ => return Vec::new(),
-    };
-    line[start..end]
-        .split_whitespace()
-        .filter(|s| !s.is_empty())
-        .map(String::from)
+impl MkinitcpioInfo {
+    pub fn is_configured(&self) -> bool {
+        !self.config_files.is_empty() || !self.presets.is_empty()
+    }
+
+    pub fn config_files(&self) -> &[String] {
+        &self.config_files
+    }
+
+    pub fn modules(&self) -> &[String] {
+        &self.modules
+    }
+
+    pub fn hooks(&self) -> &[String] {
+        &self.hooks
+    }
+
+    pub fn compression(&self) -> Option<&str> {
+        self.compression.as_deref()
+    }
+
+    pub fn image_paths(&self) -> Vec<String> {
+        self.presets
+            .iter()
+            .flat_map(|preset| preset.images.iter())
+            .filter_map(|image| image.image.clone())
+            .collect()
+    }
+}
+
+fn parse_mkinitcpio_preset(name: String, path: String, text: &str) -> MkinitcpioPresetInfo {
+    let mut vars = ShellVars::default();
+    vars.parse(text);
+    let all_kver = vars.non_empty_scalar("ALL_kver");
+    let all_config = vars.non_empty_scalar("ALL_config");
+    let images = vars
+        .array("PRESETS")
+        .into_iter()
+        .map(|preset| {
+            let get = |suffix: &str| vars.non_empty_scalar(&format!("{preset}_{suffix}"));
+            MkinitcpioPresetImageInfo {
+                kver: get("kver").or_else(|| all_kver.clone()),
+                config: get("config")
+                    .or_else(|| all_config.clone())
+                    .unwrap_or_else(|| MKINITCPIO_CONF.to_string()),
+                image: get("image"),
+                uki: get("uki").or_else(|| get("efi_image")),
+                options: vars.array(&format!("{preset}_options")),
+                preset,
+            }
+        })
+        .collect();
+    MkinitcpioPresetInfo {
+        name,
+        path,
+        all_kver,
+        all_config,
+        images,
+    }
+}
+
+fn mkinitcpio_presets() -> Vec<MkinitcpioPresetInfo> {
+    list_dir_sorted(MKINITCPIO_PRESET_DIR)
+        .into_iter()
+        .filter(|path| path.extension().is_some_and(|ext| ext == "preset"))
+        .filter_map(|path| {
+            let text = read_file_optional(&path)?;
+            let name = path.file_stem()?.to_string_lossy().into_owned();
+            Some(parse_mkinitcpio_preset(name, path_string(&path), &text))
+        })
         .collect()
 }
 
 pub fn mkinitcpio_info() -> MkinitcpioInfo {
-    let modules_line = run_command_optional("grep", &["-E", "^MODULES=", "/etc/mkinitcpio.conf"])
-        .unwrap_or_default();
-    let hooks_line = run_command_optional("grep", &["-E", "^HOOKS=", "/etc/mkinitcpio.conf"])
-        .unwrap_or_default();
+    let mut vars = ShellVars::default();
+    let mut config_files = Vec::new();
+    if let Some(text) = read_file_optional(MKINITCPIO_CONF) {
+        vars.parse(&text);
+        config_files.push(MKINITCPIO_CONF.to_string());
+    }
+    let drop_ins = list_dir_sorted(MKINITCPIO_CONF_DIR)
+        .into_iter()
+        .filter(|path| path.extension().is_some_and(|ext| ext == "conf"));
+    for path in drop_ins {
+        if let Some(text) = read_file_optional(&path) {
+            vars.parse(&text);
+            config_files.push(path_string(&path));
+        }
+    }
     MkinitcpioInfo {
-        modules: parse_mkinitcpio_list(&modules_line),
-        hooks: parse_mkinitcpio_list(&hooks_line),
+        config_files,
+        modules: vars.array("MODULES"),
+        binaries: vars.array("BINARIES"),
+        files: vars.array("FILES"),
+        hooks: vars.array("HOOKS"),
+        compression: vars.non_empty_scalar("COMPRESSION"),
+        compression_options: vars.array("COMPRESSION_OPTIONS"),
+        modules_decompress: vars.non_empty_scalar("MODULES_DECOMPRESS"),
+        presets: mkinitcpio_presets(),
     }
 }
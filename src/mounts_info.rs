@@ -0,0 +1,280 @@
+use std::collections::HashSet;
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::lsblk_info::{block_device_spec_exists, BlockDeviceInfo};
+use crate::utils::{read_file_string, unescape_octal};
+
+#[derive(Serialize)]
+pub struct MountsInfo {
+    mountinfo: Vec<MountEntryInfo>,
+    fstab: Vec<FstabEntryInfo>,
+    crypttab: Vec<CrypttabEntryInfo>,
+    warnings: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct MountEntryInfo {
+    mount_id: String,
+    parent_id: String,
+    maj_min: String,
+    root: String,
+    mount_point: String,
+    options: Vec<String>,
+    propagation: String,
+    optional_fields: Vec<String>,
+    fstype: String,
+    source: String,
+    super_options: Vec<String>,
+    bind: bool,
+}
+
+#[derive(Serialize)]
+pub struct FstabEntryInfo {
+    spec: String,
+    mount_point: String,
+    fstype: String,
+    options: Vec<String>,
+    dump: String,
+    pass: String,
+    device_found: Option<bool>,
+    mounted: Option<bool>,
+}
+
+#[derive(Serialize)]
+pub struct CrypttabEntryInfo {
+    name: String,
+    device: String,
+    key_file: String,
+    options: Vec<String>,
+    device_found: Option<bool>,
+    active: bool,
+}
+
+impl MountsInfo {
+    pub fn entries(&self) -> &[MountEntryInfo] {
+        &self.mountinfo
+    }
+}
+
+impl MountEntryInfo {
+    pub fn mount_point(&self) -> &str {
+        &self.mount_point
+    }
+
+    pub fn fstype(&self) -> &str {
+        &self.fstype
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn super_options(&self) -> &[String] {
+        &self.super_options
+    }
+}
+
+fn split_options(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .filter(|option| !option.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+fn parse_mountinfo_line(line: &str) -> Option<MountEntryInfo> {
+    let (left, right) = line.split_once(" - ")?;
+    let mut left = left.split_whitespace();
+    let mount_id = left.next()?.to_string();
+    let parent_id = left.next()?.to_string();
+    let maj_min = left.next()?.to_string();
+    let root = unescape_octal(left.next()?);
+    let mount_point = unescape_octal(left.next()?);
+    let options = split_options(left.next()?);
+    let optional_fields: Vec<String> = left.map(String::from).collect();
+    let mut right = right.split_whitespace();
+    let fstype = right.next()?.to_string();
+    let source = unescape_octal(right.next().unwrap_or_default());
+    let super_options = split_options(right.next().unwrap_or_default());
+
+    let propagation = if optional_fields.iter().any(|f| f.starts_with("shared:")) {
+        "shared"
+    } else if optional_fields.iter().any(|f| f.starts_with("master:")) {
+        "slave"
+    } else if optional_fields.iter().any(|f| f == "unbindable") {
+        "unbindable"
+    } else {
+        "private"
+    };
+
+    // A btrfs subvolume mount also has a non-"/" root ("/@home"); only count it
+    // as a bind mount when the root differs from the mounted subvolume.
+    let subvolume = (fstype == "btrfs")
+        .then(|| {
+            super_options
+                .iter()
+                .find_map(|option| option.strip_prefix("subvol="))
+        })
+        .flatten();
+    let bind = root != "/" && subvolume != Some(root.as_str());
+
+    Some(MountEntryInfo {
+        bind,
+        mount_id,
+        parent_id,
+        maj_min,
+        root,
+        mount_point,
+        options,
+        propagation: propagation.to_string(),
+        optional_fields,
+        fstype,
+        source,
+        super_options,
+    })
+}
+
+fn table_lines(text: &str) -> impl Iterator<Item = Vec<String>> + '_ {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| line.split_whitespace().map(unescape_octal).collect())
+}
+
+fn parse_fstab(
+    text: &str,
+    devices: &[BlockDeviceInfo],
+    mount_points: &HashSet<&str>,
+) -> Vec<FstabEntryInfo> {
+    table_lines(text)
+        .filter(|fields| fields.len() >= 3)
+        .map(|fields| {
+            let field = |index: usize| fields.get(index).cloned().unwrap_or_default();
+            let options = split_options(&field(3));
+            let fstype = field(2);
+            let mount_point = field(1);
+            let is_swap = fstype == "swap" || mount_point == "none" || mount_point == "swap";
+            let noauto = options.iter().any(|option| option == "noauto");
+            let mounted = (!is_swap && !noauto).then(|| {
+                let normalized = mount_point.trim_end_matches('/');
+                let normalized = if normalized.is_empty() {
+                    "/"
+                } else {
+                    normalized
+                };
+                mount_points.contains(normalized)
+            });
+            FstabEntryInfo {
+                device_found: block_device_spec_exists(&fields[0], devices),
+                spec: field(0),
+                mount_point,
+                fstype,
+                options,
+                dump: field(4),
+                pass: field(5),
+                mounted,
+            }
+        })
+        .collect()
+}
+
+fn parse_crypttab(text: &str, devices: &[BlockDeviceInfo]) -> Vec<CrypttabEntryInfo> {
+    table_lines(text)
+        .filter(|fields| fields.len() >= 2)
+        .map(|fields| {
+            let field = |index: usize| fields.get(index).cloned().unwrap_or_default();
+            CrypttabEntryInfo {
+                active: Path::new("/dev/mapper").join(&fields[0]).exists(),
+                device_found: block_device_spec_exists(&fields[1], devices),
+                name: field(0),
+                device: field(1),
+                key_file: field(2),
+                options: split_options(&field(3)),
+            }
+        })
+        .collect()
+}
+
+pub fn mounts_info(devices: &[BlockDeviceInfo]) -> MountsInfo {
+    let mountinfo: Vec<MountEntryInfo> = read_file_string("/proc/self/mountinfo")
+        .lines()
+        .filter_map(parse_mountinfo_line)
+        .collect();
+    let mount_points: HashSet<&str> = mountinfo
+        .iter()
+        .map(|entry| entry.mount_point.as_str())
+        .collect();
+    let fstab = parse_fstab(&read_file_string("/etc/fstab"), devices, &mount_points);
+    let crypttab = parse_crypttab(&read_file_string("/etc/crypttab"), devices);
+
+    let mut warnings = Vec::new();
+    for entry in &fstab {
+        if entry.device_found == Some(false) {
+            warnings.push(format!(
+                "fstab: {} for {} does not match any block device",
+                entry.spec, entry.mount_point
+            ));
+        }
+        if entry.mounted == Some(false) {
+            warnings.push(format!(
+                "fstab: {} is not currently mounted",
+                entry.mount_point
+            ));
+        }
+    }
+    for entry in &crypttab {
+        if entry.device_found == Some(false) {
+            warnings.push(format!(
+                "crypttab: {} for {} does not match any block device",
+                entry.device, entry.name
+            ));
+        }
+    }
+
+    MountsInfo {
+        mountinfo,
+        fstab,
+        crypttab,
+        warnings,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_mountinfo_lines() {
+        let entry = parse_mountinfo_line(
+            "36 25 0:32 / /mnt/my\\040disk rw,relatime shared:1 - ext4 /dev/sda1 rw,errors=remount-ro",
+        )
+        .unwrap();
+        assert_eq!(entry.mount_point, "/mnt/my disk");
+        assert_eq!(entry.options, ["rw", "relatime"]);
+        assert_eq!(entry.propagation, "shared");
+        assert_eq!(entry.fstype, "ext4");
+        assert_eq!(entry.source, "/dev/sda1");
+        assert!(!entry.bind);
+    }
+
+    #[test]
+    fn btrfs_subvolumes_are_not_bind_mounts() {
+        let subvolume = parse_mountinfo_line(
+            "40 1 0:35 /@home /home rw,relatime - btrfs /dev/nvme0n1p2 rw,ssd,subvol=/@home",
+        )
+        .unwrap();
+        assert!(!subvolume.bind);
+
+        let bind = parse_mountinfo_line(
+            "41 1 0:35 /@home/user/src /srv/src rw,relatime - btrfs /dev/nvme0n1p2 rw,ssd,subvol=/@home",
+        )
+        .unwrap();
+        assert!(bind.bind);
+
+        let ext4_bind =
+            parse_mountinfo_line("42 1 8:1 /var/lib/data /data rw - ext4 /dev/sda1 rw").unwrap();
+        assert!(ext4_bind.bind);
+    }
+}
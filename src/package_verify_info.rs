@@ -0,0 +1,360 @@
+use std::collections::HashSet;
+use std::fs;
+use std::io::ErrorKind;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+use serde::Serialize;
+
+use crate::pacman_info::{PacmanDesc, PACMAN_LOCAL_DB};
+use crate::sha256::sha256_file;
+use crate::utils::{list_dir_sorted, read_file_optional, run_command_with_input, unescape_octal};
+
+#[derive(Serialize)]
+pub struct PackageVerificationInfo {
+    database: String,
+    workers: usize,
+    packages_checked: usize,
+    files_checked: usize,
+    modified_count: usize,
+    missing_count: usize,
+    unreadable_count: usize,
+    without_mtree: Vec<String>,
+    packages: Vec<PackageVerificationResult>,
+}
+
+#[derive(Serialize)]
+pub struct PackageVerificationResult {
+    name: String,
+    version: String,
+    missing: Vec<String>,
+    modified: Vec<ModifiedFileInfo>,
+}
+
+#[derive(Serialize)]
+pub struct ModifiedFileInfo {
+    path: String,
+    backup: bool,
+    problems: Vec<String>,
+}
+
+// One entry of a package's mtree, with the /set defaults applied.
+#[derive(Default, Clone)]
+struct MtreeEntry {
+    path: String,
+    kind: Option<String>,
+    mode: Option<u32>,
+    size: Option<u64>,
+    sha256: Option<String>,
+    link: Option<String>,
+}
+
+impl MtreeEntry {
+    fn apply(&mut self, key: &str, value: Option<&str>) {
+        match (key, value) {
+            ("type", Some(value)) => self.kind = Some(value.to_string()),
+            ("mode", Some(value)) => self.mode = u32::from_str_radix(value, 8).ok(),
+            ("size", Some(value)) => self.size = value.parse().ok(),
+            ("sha256digest", Some(value)) => self.sha256 = Some(value.to_ascii_lowercase()),
+            ("link", Some(value)) => self.link = Some(unescape_octal(value)),
+            ("type", None) => self.kind = None,
+            ("mode", None) => self.mode = None,
+            _ => {}
+        }
+    }
+}
+
+enum FileCheck {
+    Ok,
+    Missing,
+    Unreadable,
+    Modified(Vec<String>),
+}
+
+fn parse_mtree(text: &str) -> Vec<MtreeEntry> {
+    let mut defaults = MtreeEntry::default();
+    let mut entries = Vec::new();
+    for line in text.lines() {
+        let mut words = line.split_whitespace();
+        let Some(first) = words.next() else {
+            continue;
+        };
+        match first {
+            "/set" => {
+                for word in words {
+                    if let Some((key, value)) = word.split_once('=') {
+                        defaults.apply(key, Some(value));
+                    }
+                }
+            }
+            "/unset" => {
+                for key in words {
+                    defaults.apply(key, None);
+                }
+            }
+            _ if first.starts_with('#') => {}
+            _ => {
+                // Package metadata (.PKGINFO, .BUILDINFO, ...) is not installed.
+                let Some(path) = first.strip_prefix("./") else {
+                    continue;
+                };
+                if path.starts_with('.') && !path.contains('/') {
+                    continue;
+                }
+                let mut entry = defaults.clone();
+                entry.path = format!("/{}", unescape_octal(path));
+                for word in words {
+                    if let Some((key, value)) = word.split_once('=') {
+                        entry.apply(key, Some(value));
+                    }
+                }
+                entries.push(entry);
+            }
+        }
+    }
+    entries
+}
+
+fn read_mtree(dir: &Path) -> Option<Vec<MtreeEntry>> {
+    let compressed = fs::read(dir.join("mtree")).ok()?;
+    let text = run_command_with_input("gzip", &["-dc"], &compressed)?;
+    Some(parse_mtree(&String::from_utf8_lossy(&text)))
+}
+
+fn check_entry(entry: &MtreeEntry) -> FileCheck {
+    let metadata = match fs::symlink_metadata(&entry.path) {
+        Ok(metadata) => metadata,
+        Err(err) if err.kind() == ErrorKind::NotFound => return FileCheck::Missing,
+        Err(_) => return FileCheck::Unreadable,
+    };
+    let file_type = metadata.file_type();
+    let actual_kind = if file_type.is_symlink() {
+        "link"
+    } else if file_type.is_dir() {
+        "dir"
+    } else if file_type.is_file() {
+        "file"
+    } else {
+        "other"
+    };
+    let expected_kind = entry.kind.as_deref().unwrap_or("file");
+    if actual_kind != expected_kind {
+        return FileCheck::Modified(vec![format!(
+            "type: expected {expected_kind}, found {actual_kind}"
+        )]);
+    }
+
+    let mut problems = Vec::new();
+    if actual_kind == "link" {
+        if let Some(expected) = &entry.link {
+            let target = fs::read_link(&entry.path)
+                .map(|target| target.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            if &target != expected {
+                problems.push(format!("link: expected {expected}, found {target}"));
+            }
+        }
+        return modified_or_ok(problems);
+    }
+    let mode = metadata.permissions().mode() & 0o7777;
+    if let Some(expected) = entry.mode
+        && mode != expected
+    {
+        problems.push(format!("mode: expected {expected:o}, found {mode:o}"));
+    }
+    if actual_kind == "file" {
+        let size_matches = entry.size.is_none_or(|expected| expected == metadata.len());
+        if let Some(expected) = entry.size
+            && !size_matches
+        {
+            problems.push(format!(
+                "size: expected {expected}, found {}",
+                metadata.len()
+            ));
+        }
+        // A size mismatch already proves the content changed; skip the hash.
+        if size_matches && let Some(expected) = &entry.sha256 {
+            match sha256_file(Path::new(&entry.path)) {
+                Ok(digest) if &digest != expected => problems.push("sha256".to_string()),
+                Ok(_) => {}
+                Err(_) => return FileCheck::Unreadable,
+            }
+        }
+    }
+    modified_or_ok(problems)
+}
+
+fn modified_or_ok(problems: Vec<String>) -> FileCheck {
+    if problems.is_empty() {
+        FileCheck::Ok
+    } else {
+        FileCheck::Modified(problems)
+    }
+}
+
+struct PackageOutcome {
+    result: PackageVerificationResult,
+    has_mtree: bool,
+    files_checked: usize,
+    unreadable: usize,
+}
+
+fn verify_package(dir: &Path) -> Option<PackageOutcome> {
+    let desc = PacmanDesc::parse(&read_file_optional(dir.join("desc"))?);
+    let name = desc.first("NAME")?;
+    let version = desc.first("VERSION").unwrap_or_default();
+    // %BACKUP% lines are "etc/pacman.conf<TAB>md5"; edits to these are expected.
+    let backup: HashSet<String> = desc
+        .list("BACKUP")
+        .iter()
+        .filter_map(|line| line.split('\t').next())
+        .map(|path| format!("/{path}"))
+        .collect();
+    let mut outcome = PackageOutcome {
+        result: PackageVerificationResult {
+            name,
+            version,
+            missing: Vec::new(),
+            modified: Vec::new(),
+        },
+        has_mtree: false,
+        files_checked: 0,
+        unreadable: 0,
+    };
+    let Some(entries) = read_mtree(dir) else {
+        return Some(outcome);
+    };
+    outcome.has_mtree = true;
+    for entry in entries {
+        outcome.files_checked += 1;
+        match check_entry(&entry) {
+            FileCheck::Ok => {}
+            FileCheck::Missing => outcome.result.missing.push(entry.path),
+            FileCheck::Unreadable => outcome.unreadable += 1,
+            FileCheck::Modified(problems) => outcome.result.modified.push(ModifiedFileInfo {
+                backup: backup.contains(&entry.path),
+                path: entry.path,
+                problems,
+            }),
+        }
+    }
+    Some(outcome)
+}
+
+pub fn package_verify_info() -> PackageVerificationInfo {
+    let dirs: Vec<PathBuf> = list_dir_sorted(PACMAN_LOCAL_DB)
+        .into_iter()
+        .filter(|dir| dir.is_dir())
+        .collect();
+    let workers = thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+        .min(dirs.len().max(1));
+    // Workers pull package indexes from a shared counter so that a few large
+    // packages do not leave the other threads idle.
+    let next = AtomicUsize::new(0);
+    let outcomes: Mutex<Vec<(usize, PackageOutcome)>> = Mutex::new(Vec::new());
+    thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(dir) = dirs.get(index) else {
+                    break;
+                };
+                if let Some(outcome) = verify_package(dir) {
+                    outcomes.lock().unwrap().push((index, outcome));
+                }
+            });
+        }
+    });
+    let mut outcomes = outcomes.into_inner().unwrap();
+    outcomes.sort_by_key(|(index, _)| *index);
+
+    let mut info = PackageVerificationInfo {
+        database: PACMAN_LOCAL_DB.to_string(),
+        workers,
+        packages_checked: 0,
+        files_checked: 0,
+        modified_count: 0,
+        missing_count: 0,
+        unreadable_count: 0,
+        without_mtree: Vec::new(),
+        packages: Vec::new(),
+    };
+    for (_, outcome) in outcomes {
+        if !outcome.has_mtree {
+            info.without_mtree.push(outcome.result.name);
+            continue;
+        }
+        info.packages_checked += 1;
+        info.files_checked += outcome.files_checked;
+        info.unreadable_count += outcome.unreadable;
+        info.modified_count += outcome.result.modified.len();
+        info.missing_count += outcome.result.missing.len();
+        if !outcome.result.missing.is_empty() || !outcome.result.modified.is_empty() {
+            info.packages.push(outcome.result);
+        }
+    }
+    info
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MTREE_FIXTURE: &str = "\
+#mtree
+/set type=file uid=0 gid=0 mode=644
+./.BUILDINFO time=1700000000.0 size=5000 md5digest=00 sha256digest=00
+./.PKGINFO time=1700000000.0 size=600 md5digest=00 sha256digest=00
+./usr time=1700000000.0 mode=755 type=dir
+./usr/bin/tool time=1700000000.0 mode=755 size=1234 sha256digest=ABCDEF
+./usr/lib/libtool.so time=1700000000.0 type=link link=libtool.so.1
+/unset mode
+./usr/share/doc/my\\040file.txt time=1700000000.0 size=12 sha256digest=0123
+";
+
+    #[test]
+    fn parses_mtree_with_set_defaults() {
+        let entries = parse_mtree(MTREE_FIXTURE);
+        let paths: Vec<&str> = entries.iter().map(|entry| entry.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            [
+                "/usr",
+                "/usr/bin/tool",
+                "/usr/lib/libtool.so",
+                "/usr/share/doc/my file.txt"
+            ]
+        );
+
+        assert_eq!(entries[0].kind.as_deref(), Some("dir"));
+        assert_eq!(entries[0].mode, Some(0o755));
+
+        let tool = &entries[1];
+        assert_eq!(tool.kind.as_deref(), Some("file"));
+        assert_eq!(tool.mode, Some(0o755));
+        assert_eq!(tool.size, Some(1234));
+        assert_eq!(tool.sha256.as_deref(), Some("abcdef"));
+
+        let link = &entries[2];
+        assert_eq!(link.kind.as_deref(), Some("link"));
+        assert_eq!(link.mode, Some(0o644));
+        assert_eq!(link.link.as_deref(), Some("libtool.so.1"));
+
+        // After "/unset mode" the /set default no longer applies.
+        assert_eq!(entries[3].mode, None);
+        assert_eq!(entries[3].kind.as_deref(), Some("file"));
+    }
+
+    #[test]
+    fn unescapes_octal_sequences() {
+        assert_eq!(unescape_octal("a\\040b\\043c"), "a b#c");
+        assert_eq!(unescape_octal("caf\\303\\251"), "café");
+        // Not an octal sequence: kept as is.
+        assert_eq!(unescape_octal("trailing\\04"), "trailing\\04");
+    }
+}
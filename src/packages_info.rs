@@ -0,0 +1,470 @@
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::app_sources_info::{app_sources_info, AppSourcesInfo};
+use crate::pacman_info::PacmanInfo;
+use crate::utils::{list_dir_sorted, read_file_optional, run_command_optional};
+
+const DPKG_STATUS: &str = "/var/lib/dpkg/status";
+const APT_EXTENDED_STATES: &str = "/var/lib/apt/extended_states";
+const RPM_DB_DIRS: [&str; 2] = ["/var/lib/rpm", "/usr/lib/sysimage/rpm"];
+const APK_INSTALLED: &str = "/lib/apk/db/installed";
+const APK_WORLD: &str = "/etc/apk/world";
+const XBPS_DB_DIR: &str = "/var/db/xbps";
+
+#[derive(Serialize)]
+pub struct PackagesInfo {
+    backends: Vec<PackageBackendInfo>,
+    user_sources: AppSourcesInfo,
+}
+
+#[derive(Serialize)]
+pub struct PackageBackendInfo {
+    name: String,
+    database: String,
+    package_count: usize,
+    explicit_count: Option<usize>,
+    listed_in: Option<String>,
+    packages: Vec<PackageRecordInfo>,
+}
+
+#[derive(Serialize)]
+pub struct PackageRecordInfo {
+    name: String,
+    version: String,
+    arch: Option<String>,
+    explicit: Option<bool>,
+}
+
+impl PackageRecordInfo {
+    fn new(name: String, version: String, arch: Option<String>, explicit: Option<bool>) -> Self {
+        PackageRecordInfo {
+            name,
+            version,
+            arch,
+            explicit,
+        }
+    }
+}
+
+impl PackageBackendInfo {
+    fn new(name: &str, database: &str, packages: Vec<PackageRecordInfo>) -> Self {
+        // Only report an explicit count when the backend records install reasons.
+        let explicit_count = packages.iter().any(|pkg| pkg.explicit.is_some()).then(|| {
+            packages
+                .iter()
+                .filter(|pkg| pkg.explicit == Some(true))
+                .count()
+        });
+        PackageBackendInfo {
+            name: name.to_string(),
+            database: database.to_string(),
+            package_count: packages.len(),
+            explicit_count,
+            listed_in: None,
+            packages,
+        }
+    }
+
+    // For backends whose package list is already reported in another section.
+    fn summary(
+        name: &str,
+        database: &str,
+        package_count: usize,
+        explicit_count: usize,
+        listed_in: &str,
+    ) -> Self {
+        PackageBackendInfo {
+            name: name.to_string(),
+            database: database.to_string(),
+            package_count,
+            explicit_count: Some(explicit_count),
+            listed_in: Some(listed_in.to_string()),
+            packages: Vec::new(),
+        }
+    }
+}
+
+// Debian control-style stanzas ("Field: value", blank-line separated);
+// continuation lines are irrelevant for the fields read here.
+fn parse_stanzas(text: &str) -> Vec<HashMap<String, String>> {
+    let mut stanzas = Vec::new();
+    let mut current = HashMap::new();
+    for line in text.lines() {
+        if line.trim().is_empty() {
+            if !current.is_empty() {
+                stanzas.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+        if line.starts_with(char::is_whitespace) {
+            continue;
+        }
+        if let Some((key, value)) = line.split_once(':') {
+            current.insert(key.trim().to_string(), value.trim().to_string());
+        }
+    }
+    if !current.is_empty() {
+        stanzas.push(current);
+    }
+    stanzas
+}
+
+fn parse_dpkg_status(status: &str, extended_states: Option<&str>) -> Vec<PackageRecordInfo> {
+    // apt marks automatically installed packages; without the file the reason is unknown.
+    // extended_states records "Architecture: all" packages under the native arch,
+    // so those are matched by name alone.
+    let auto_installed: Option<HashSet<(String, String)>> = extended_states.map(|text| {
+        parse_stanzas(text)
+            .into_iter()
+            .filter(|stanza| stanza.get("Auto-Installed").map(String::as_str) == Some("1"))
+            .filter_map(|stanza| {
+                let name = stanza.get("Package")?.clone();
+                let arch = stanza.get("Architecture").cloned().unwrap_or_default();
+                Some((name, arch))
+            })
+            .collect()
+    });
+    parse_stanzas(status)
+        .into_iter()
+        .filter(|stanza| {
+            stanza
+                .get("Status")
+                .is_some_and(|status| status.ends_with(" installed"))
+        })
+        .filter_map(|stanza| {
+            let name = stanza.get("Package")?.clone();
+            let arch = stanza.get("Architecture").cloned();
+            let explicit = auto_installed.as_ref().map(|auto| {
+                let arch = arch.as_deref().unwrap_or_default();
+                if arch == "all" {
+                    !auto.iter().any(|(auto_name, _)| *auto_name == name)
+                } else {
+                    !auto.contains(&(name.clone(), arch.to_string()))
+                }
+            });
+            Some(PackageRecordInfo::new(
+                name,
+                stanza.get("Version").cloned().unwrap_or_default(),
+                arch,
+                explicit,
+            ))
+        })
+        .collect()
+}
+
+fn dpkg_backend() -> Option<PackageBackendInfo> {
+    let status = read_file_optional(DPKG_STATUS)?;
+    let extended_states = read_file_optional(APT_EXTENDED_STATES);
+    let packages = parse_dpkg_status(&status, extended_states.as_deref());
+    Some(PackageBackendInfo::new("dpkg", DPKG_STATUS, packages))
+}
+
+fn rpm_backend() -> Option<PackageBackendInfo> {
+    let database = RPM_DB_DIRS.iter().find(|dir| Path::new(dir).is_dir())?;
+    let output = run_command_optional(
+        "rpm",
+        &[
+            "-qa",
+            "--queryformat",
+            "%{NAME}\\t%|EPOCH?{%{EPOCH}:}:{}|%{VERSION}-%{RELEASE}\\t%{ARCH}\\n",
+        ],
+    )?;
+    let mut packages: Vec<PackageRecordInfo> = output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\t');
+            let name = fields.next()?.to_string();
+            let version = fields.next()?.to_string();
+            let arch = fields
+                .next()
+                .filter(|arch| !arch.is_empty() && *arch != "(none)")
+                .map(String::from);
+            // rpm itself does not track why a package was installed.
+            Some(PackageRecordInfo::new(name, version, arch, None))
+        })
+        .collect();
+    packages.sort_by(|a, b| a.name.cmp(&b.name));
+    Some(PackageBackendInfo::new("rpm", database, packages))
+}
+
+// apk's world file lists explicit dependencies, e.g. "curl", "foo>1.2" or "bar@testing".
+fn apk_world() -> Option<HashSet<String>> {
+    let text = read_file_optional(APK_WORLD)?;
+    Some(
+        text.split_whitespace()
+            .map(|entry| {
+                let end = entry.find(['<', '>', '=', '~', '@']).unwrap_or(entry.len());
+                entry[..end].to_string()
+            })
+            .collect(),
+    )
+}
+
+fn apk_backend() -> Option<PackageBackendInfo> {
+    let installed = read_file_optional(APK_INSTALLED)?;
+    let world = apk_world();
+    let mut packages = Vec::new();
+    let mut fields: HashMap<char, String> = HashMap::new();
+    // Records are blocks of single-letter "X:value" lines separated by blank lines.
+    for line in installed.lines().chain(std::iter::once("")) {
+        if line.is_empty() {
+            if let Some(name) = fields.remove(&'P') {
+                let explicit = world.as_ref().map(|world| world.contains(&name));
+                packages.push(PackageRecordInfo::new(
+                    name,
+                    fields.remove(&'V').unwrap_or_default(),
+                    fields.remove(&'A'),
+                    explicit,
+                ));
+            }
+            fields.clear();
+            continue;
+        }
+        let mut chars = line.chars();
+        if let (Some(key), Some(':')) = (chars.next(), chars.next()) {
+            fields
+                .entry(key)
+                .or_insert_with(|| chars.as_str().to_string());
+        }
+    }
+    packages.sort_by(|a, b| a.name.cmp(&b.name));
+    Some(PackageBackendInfo::new("apk", APK_INSTALLED, packages))
+}
+
+fn xml_unescape(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+// xbps keeps its package database as an XML property list: a top-level dict of
+// package name → dict with "pkgver" ("name-1.2_1"), "architecture" and
+// "automatic-install" keys.
+fn parse_xbps_pkgdb(text: &str) -> Vec<PackageRecordInfo> {
+    let mut packages = Vec::new();
+    let mut depth = 0usize;
+    let mut key: Option<String> = None;
+    let mut package: Option<String> = None;
+    let mut fields: HashMap<String, String> = HashMap::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('<') {
+        let Some(end) = rest[start..].find('>') else {
+            break;
+        };
+        let tag = &rest[start + 1..start + end];
+        rest = &rest[start + end + 1..];
+        let content = || xml_unescape(rest.split('<').next().unwrap_or_default().trim());
+        match tag {
+            "dict" => depth += 1,
+            "/dict" => {
+                if depth == 2 {
+                    if let Some(name) = package.take() {
+                        let pkgver = fields.remove("pkgver").unwrap_or_default();
+                        let version = pkgver
+                            .strip_prefix(&format!("{name}-"))
+                            .unwrap_or(&pkgver)
+                            .to_string();
+                        let automatic =
+                            fields.remove("automatic-install").as_deref() == Some("true");
+                        packages.push(PackageRecordInfo::new(
+                            name,
+                            version,
+                            fields.remove("architecture"),
+                            Some(!automatic),
+                        ));
+                    }
+                    fields.clear();
+                }
+                depth = depth.saturating_sub(1);
+            }
+            "key" => {
+                let value = content();
+                if depth == 1 {
+                    // Internal entries such as _XBPS_ALTERNATIVES_ are not packages.
+                    package = Some(value).filter(|key| !key.starts_with("_XBPS_"));
+                } else {
+                    key = Some(value);
+                }
+            }
+            "string" | "integer" if depth == 2 => {
+                if let Some(key) = key.take() {
+                    fields.insert(key, content());
+                }
+            }
+            "true/" | "false/" if depth == 2 => {
+                if let Some(key) = key.take() {
+                    fields.insert(key, tag.trim_end_matches('/').to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+    packages.sort_by(|a, b| a.name.cmp(&b.name));
+    packages
+}
+
+fn xbps_backend() -> Option<PackageBackendInfo> {
+    let path = list_dir_sorted(XBPS_DB_DIR)
+        .into_iter()
+        .rev()
+        .find(|path| {
+            path.file_name().is_some_and(|name| {
+                let name = name.to_string_lossy();
+                name.starts_with("pkgdb-") && name.ends_with(".plist")
+            })
+        })?;
+    let text = read_file_optional(&path)?;
+    Some(PackageBackendInfo::new(
+        "xbps",
+        &path.to_string_lossy(),
+        parse_xbps_pkgdb(&text),
+    ))
+}
+
+pub fn packages_info(pacman: &PacmanInfo) -> PackagesInfo {
+    let pacman_backend = (pacman.package_count() > 0).then(|| {
+        PackageBackendInfo::summary(
+            "pacman",
+            pacman.database(),
+            pacman.package_count(),
+            pacman.explicit_count(),
+            "pacman.packages",
+        )
+    });
+    let backends = [
+        pacman_backend,
+        dpkg_backend(),
+        rpm_backend(),
+        apk_backend(),
+        xbps_backend(),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    PackagesInfo {
+        backends,
+        user_sources: app_sources_info(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DPKG_STATUS_FIXTURE: &str = "\
+Package: bash
+Status: install ok installed
+Architecture: amd64
+Version: 5.2.15-2
+Description: GNU Bourne Again SHell
+ continuation line: ignored
+
+Package: tzdata
+Status: install ok installed
+Architecture: all
+Version: 2024a-0
+
+Package: removed
+Status: deinstall ok config-files
+Architecture: amd64
+Version: 1.0
+";
+
+    const EXTENDED_STATES_FIXTURE: &str = "\
+Package: tzdata
+Architecture: amd64
+Auto-Installed: 1
+
+Package: bash
+Architecture: amd64
+Auto-Installed: 0
+";
+
+    #[test]
+    fn parses_control_stanzas() {
+        let stanzas = parse_stanzas(DPKG_STATUS_FIXTURE);
+        assert_eq!(stanzas.len(), 3);
+        assert_eq!(stanzas[0]["Package"], "bash");
+        assert_eq!(stanzas[0]["Description"], "GNU Bourne Again SHell");
+        assert_eq!(stanzas[2]["Status"], "deinstall ok config-files");
+    }
+
+    #[test]
+    fn dpkg_installed_packages_and_reasons() {
+        let packages = parse_dpkg_status(DPKG_STATUS_FIXTURE, Some(EXTENDED_STATES_FIXTURE));
+        let summary: Vec<(&str, &str, Option<bool>)> = packages
+            .iter()
+            .map(|pkg| (pkg.name.as_str(), pkg.version.as_str(), pkg.explicit))
+            .collect();
+        // tzdata is "all" in status but recorded under the native arch by apt.
+        assert_eq!(
+            summary,
+            [
+                ("bash", "5.2.15-2", Some(true)),
+                ("tzdata", "2024a-0", Some(false)),
+            ]
+        );
+        let unknown = parse_dpkg_status(DPKG_STATUS_FIXTURE, None);
+        assert!(unknown.iter().all(|pkg| pkg.explicit.is_none()));
+    }
+
+    #[test]
+    fn parses_xbps_pkgdb() {
+        let plist = r#"<?xml version="1.0" encoding="UTF-8"?>
+<plist version="1.0">
+<dict>
+	<key>_XBPS_ALTERNATIVES_</key>
+	<dict>
+		<key>sh</key>
+		<array>
+			<string>bash</string>
+		</array>
+	</dict>
+	<key>bash</key>
+	<dict>
+		<key>architecture</key>
+		<string>x86_64</string>
+		<key>automatic-install</key>
+		<true/>
+		<key>pkgver</key>
+		<string>bash-5.2.21_1</string>
+	</dict>
+	<key>xbps</key>
+	<dict>
+		<key>architecture</key>
+		<string>x86_64</string>
+		<key>pkgver</key>
+		<string>xbps-0.59.2_1</string>
+		<key>short_desc</key>
+		<string>The X Binary Package System &amp; more</string>
+	</dict>
+</dict>
+</plist>
+"#;
+        let packages = parse_xbps_pkgdb(plist);
+        let summary: Vec<(&str, &str, Option<&str>, Option<bool>)> = packages
+            .iter()
+            .map(|pkg| {
+                (
+                    pkg.name.as_str(),
+                    pkg.version.as_str(),
+                    pkg.arch.as_deref(),
+                    pkg.explicit,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("bash", "5.2.21_1", Some("x86_64"), Some(false)),
+                ("xbps", "0.59.2_1", Some("x86_64"), Some(true)),
+            ]
+        );
+    }
+}
@@ -0,0 +1,326 @@
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::utils::{
+    file_mtime_secs, list_dir_sorted, now_secs, path_string, read_file_optional, read_file_string,
+};
+
+const PACMAN_CONF: &str = "/etc/pacman.conf";
+const PACMAN_MIRRORLIST: &str = "/etc/pacman.d/mirrorlist";
+const PACMAN_SYNC_DB: &str = "/var/lib/pacman/sync";
+const TOP_MIRRORS: usize = 5;
+const MAX_INCLUDE_DEPTH: usize = 8;
+
+#[derive(Serialize)]
+pub struct PacmanConfigInfo {
+    config_files: Vec<String>,
+    architecture: Vec<String>,
+    sig_level: Option<String>,
+    local_file_sig_level: Option<String>,
+    remote_file_sig_level: Option<String>,
+    parallel_downloads: Option<u32>,
+    hold_pkg: Vec<String>,
+    ignore_pkg: Vec<String>,
+    ignore_group: Vec<String>,
+    flags: Vec<String>,
+    repositories: Vec<PacmanRepoInfo>,
+    top_mirrors: Vec<String>,
+    sync_databases: Vec<PacmanSyncDbInfo>,
+}
+
+#[derive(Serialize)]
+pub struct PacmanRepoInfo {
+    name: String,
+    sig_level: Option<String>,
+    usage: Option<String>,
+    includes: Vec<String>,
+    server_count: usize,
+    servers: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct PacmanSyncDbInfo {
+    name: String,
+    path: String,
+    modified: Option<u64>,
+    age_seconds: Option<u64>,
+}
+
+impl PacmanRepoInfo {
+    fn new(name: String) -> Self {
+        PacmanRepoInfo {
+            name,
+            sig_level: None,
+            usage: None,
+            includes: Vec::new(),
+            server_count: 0,
+            servers: Vec::new(),
+        }
+    }
+}
+
+// Include accepts a glob; only '*' in the file name is supported, which covers
+// the usual "/etc/pacman.d/*.conf" layout.
+fn expand_include(pattern: &str) -> Vec<String> {
+    let path = Path::new(pattern);
+    let file_pattern = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let Some((prefix, suffix)) = file_pattern.split_once('*') else {
+        return vec![pattern.to_string()];
+    };
+    let dir = path.parent().unwrap_or(Path::new("/"));
+    list_dir_sorted(dir)
+        .into_iter()
+        .filter(|entry| {
+            entry.file_name().is_some_and(|name| {
+                let name = name.to_string_lossy();
+                name.len() >= prefix.len() + suffix.len()
+                    && name.starts_with(prefix)
+                    && name.ends_with(suffix)
+            })
+        })
+        .map(path_string)
+        .collect()
+}
+
+fn split_values(value: &str) -> Vec<String> {
+    value.split_whitespace().map(String::from).collect()
+}
+
+struct PacmanConfParser {
+    info: PacmanConfigInfo,
+    section: Option<String>,
+}
+
+impl PacmanConfParser {
+    fn new() -> Self {
+        PacmanConfParser {
+            info: PacmanConfigInfo {
+                config_files: Vec::new(),
+                architecture: Vec::new(),
+                sig_level: None,
+                local_file_sig_level: None,
+                remote_file_sig_level: None,
+                parallel_downloads: None,
+                hold_pkg: Vec::new(),
+                ignore_pkg: Vec::new(),
+                ignore_group: Vec::new(),
+                flags: Vec::new(),
+                repositories: Vec::new(),
+                top_mirrors: Vec::new(),
+                sync_databases: Vec::new(),
+            },
+            section: None,
+        }
+    }
+
+    fn parse_file(&mut self, path: &str, depth: usize) {
+        let Some(text) = read_file_optional(path) else {
+            return;
+        };
+        if !self.info.config_files.iter().any(|file| file == path) {
+            self.info.config_files.push(path.to_string());
+        }
+        for line in text.lines() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                let name = name.trim().to_string();
+                if name != "options" {
+                    self.info
+                        .repositories
+                        .push(PacmanRepoInfo::new(name.clone()));
+                }
+                self.section = Some(name);
+                continue;
+            }
+            let (key, value) = match line.split_once('=') {
+                Some((key, value)) => (key.trim(), value.trim()),
+                None => (line, ""),
+            };
+            if key == "Include" {
+                if depth < MAX_INCLUDE_DEPTH {
+                    for include in expand_include(value) {
+                        if let Some(repo) = self.current_repo() {
+                            repo.includes.push(include.clone());
+                        }
+                        // A section header inside an included file applies to
+                        // that file only, not to the lines after the Include.
+                        let section = self.section.clone();
+                        self.parse_file(&include, depth + 1);
+                        self.section = section;
+                    }
+                }
+                continue;
+            }
+            match self.section.as_deref() {
+                Some("options") => self.option(key, value),
+                Some(_) => self.repo_option(key, value),
+                None => {}
+            }
+        }
+    }
+
+    fn current_repo(&mut self) -> Option<&mut PacmanRepoInfo> {
+        match self.section.as_deref() {
+            Some("options") | None => None,
+            Some(name) => self
+                .info
+                .repositories
+                .iter_mut()
+                .rev()
+                .find(|repo| repo.name == name),
+        }
+    }
+
+    fn option(&mut self, key: &str, value: &str) {
+        let info = &mut self.info;
+        match key {
+            "Architecture" => info.architecture.extend(split_values(value)),
+            "SigLevel" => info.sig_level = Some(value.to_string()),
+            "LocalFileSigLevel" => info.local_file_sig_level = Some(value.to_string()),
+            "RemoteFileSigLevel" => info.remote_file_sig_level = Some(value.to_string()),
+            "ParallelDownloads" => info.parallel_downloads = value.parse().ok(),
+            "HoldPkg" => info.hold_pkg.extend(split_values(value)),
+            "IgnorePkg" => info.ignore_pkg.extend(split_values(value)),
+            "IgnoreGroup" => info.ignore_group.extend(split_values(value)),
+            _ if value.is_empty() => info.flags.push(key.to_string()),
+            _ => {}
+        }
+    }
+
+    fn repo_option(&mut self, key: &str, value: &str) {
+        let Some(repo) = self.current_repo() else {
+            return;
+        };
+        match key {
+            "Server" => {
+                repo.servers.push(value.to_string());
+                repo.server_count += 1;
+            }
+            "SigLevel" => repo.sig_level = Some(value.to_string()),
+            "Usage" => repo.usage = Some(value.to_string()),
+            _ => {}
+        }
+    }
+}
+
+fn top_mirrors() -> Vec<String> {
+    read_file_string(PACMAN_MIRRORLIST)
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.trim().split_once('=')?;
+            (key.trim() == "Server").then(|| value.trim().to_string())
+        })
+        .take(TOP_MIRRORS)
+        .collect()
+}
+
+fn sync_databases() -> Vec<PacmanSyncDbInfo> {
+    let now = now_secs();
+    list_dir_sorted(PACMAN_SYNC_DB)
+        .into_iter()
+        .filter_map(|path| {
+            let file_name = path.file_name()?.to_string_lossy().into_owned();
+            let name = file_name.strip_suffix(".db")?.to_string();
+            let modified = file_mtime_secs(&path);
+            Some(PacmanSyncDbInfo {
+                name,
+                path: path_string(&path),
+                modified,
+                age_seconds: modified.map(|mtime| now.saturating_sub(mtime)),
+            })
+        })
+        .collect()
+}
+
+pub fn pacman_config_info() -> Option<PacmanConfigInfo> {
+    let mut parser = PacmanConfParser::new();
+    parser.parse_file(PACMAN_CONF, 0);
+    let mut info = parser.info;
+    if info.config_files.is_empty() {
+        return None;
+    }
+    // Mirrorlists are long; only the server count and top entries are kept.
+    for repo in &mut info.repositories {
+        repo.servers.truncate(TOP_MIRRORS);
+    }
+    info.top_mirrors = top_mirrors();
+    info.sync_databases = sync_databases();
+    Some(info)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    const PACMAN_CONF_FIXTURE: &str = "\
+[options]
+HoldPkg = pacman glibc
+Architecture = auto
+ParallelDownloads = 5
+Color
+SigLevel = Required DatabaseOptional
+
+[core]
+Include = @DIR@/mirrorlist
+
+[custom]
+SigLevel = Optional TrustAll
+Include = @DIR@/extra.conf
+Server = file:///srv/repo
+";
+
+    const MIRRORLIST_FIXTURE: &str = "\
+# Generated by reflector
+Server = https://mirror.example.org/archlinux/$repo/os/$arch
+Server = https://mirror.example.net/archlinux/$repo/os/$arch
+";
+
+    // An included file may open its own section; it must not leak back into
+    // the including file.
+    const EXTRA_CONF_FIXTURE: &str = "\
+[extra]
+Server = https://mirror.example.org/archlinux/$repo/os/$arch
+";
+
+    #[test]
+    fn parses_config_with_includes() {
+        let dir = std::env::temp_dir().join(format!("pacman-conf-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let dir_str = dir.to_string_lossy().into_owned();
+        let conf = dir.join("pacman.conf");
+        fs::write(&conf, PACMAN_CONF_FIXTURE.replace("@DIR@", &dir_str)).unwrap();
+        fs::write(dir.join("mirrorlist"), MIRRORLIST_FIXTURE).unwrap();
+        fs::write(dir.join("extra.conf"), EXTRA_CONF_FIXTURE).unwrap();
+
+        let mut parser = PacmanConfParser::new();
+        parser.parse_file(&conf.to_string_lossy(), 0);
+        fs::remove_dir_all(&dir).unwrap();
+        let info = parser.info;
+
+        assert_eq!(info.config_files.len(), 3);
+        assert_eq!(info.hold_pkg, ["pacman", "glibc"]);
+        assert_eq!(info.architecture, ["auto"]);
+        assert_eq!(info.parallel_downloads, Some(5));
+        assert_eq!(info.flags, ["Color"]);
+        assert_eq!(info.sig_level.as_deref(), Some("Required DatabaseOptional"));
+
+        let names: Vec<&str> = info.repositories.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, ["core", "custom", "extra"]);
+        let core = &info.repositories[0];
+        assert_eq!(core.server_count, 2);
+        assert_eq!(core.includes, [format!("{dir_str}/mirrorlist")]);
+        let custom = &info.repositories[1];
+        assert_eq!(custom.sig_level.as_deref(), Some("Optional TrustAll"));
+        assert_eq!(custom.servers, ["file:///srv/repo"]);
+        assert_eq!(info.repositories[2].server_count, 1);
+    }
+}
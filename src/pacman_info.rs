@@ -1,19 +1,219 @@
+use std::collections::{HashMap, HashSet};
+
 use serde::Serialize;
 
-use crate::utils::run_command_optional;
+use crate::pacman_config_info::{pacman_config_info, PacmanConfigInfo};
+use crate::pacman_log_info::{pacman_log_info, PacmanLogInfo};
+use crate::utils::{list_dir_sorted, read_file_optional, run_command_optional};
+
+pub const PACMAN_LOCAL_DB: &str = "/var/lib/pacman/local";
 
 #[derive(Serialize)]
 pub struct PacmanInfo {
     explicit: Vec<String>,
+    package_count: usize,
+    explicit_count: usize,
+    foreign_count: usize,
+    orphan_count: usize,
+    packages: Vec<PacmanPackageInfo>,
+    config: Option<PacmanConfigInfo>,
+    log: Option<PacmanLogInfo>,
+}
+
+#[derive(Serialize)]
+pub struct PacmanPackageInfo {
+    name: String,
+    version: String,
+    arch: Option<String>,
+    reason: String,
+    install_date: Option<u64>,
+    size_bytes: Option<u64>,
+    repository: Option<String>,
+    foreign: bool,
+    orphan: bool,
+    optional_only: bool,
+}
+
+impl PacmanInfo {
+    pub fn database(&self) -> &'static str {
+        PACMAN_LOCAL_DB
+    }
+
+    pub fn package_count(&self) -> usize {
+        self.package_count
+    }
+
+    pub fn explicit_count(&self) -> usize {
+        self.explicit_count
+    }
+}
+
+// Parsed %SECTION% blocks of a local database desc file.
+pub struct PacmanDesc {
+    fields: HashMap<String, Vec<String>>,
+}
+
+impl PacmanDesc {
+    pub fn parse(text: &str) -> Self {
+        let mut fields: HashMap<String, Vec<String>> = HashMap::new();
+        let mut current: Option<String> = None;
+        for line in text.lines() {
+            let line = line.trim_end();
+            if line.len() > 2 && line.starts_with('%') && line.ends_with('%') {
+                let key = line.trim_matches('%').to_string();
+                fields.entry(key.clone()).or_default();
+                current = Some(key);
+            } else if line.is_empty() {
+                current = None;
+            } else if let Some(key) = &current {
+                fields
+                    .entry(key.clone())
+                    .or_default()
+                    .push(line.to_string());
+            }
+        }
+        PacmanDesc { fields }
+    }
+
+    pub fn first(&self, key: &str) -> Option<String> {
+        self.fields.get(key)?.first().cloned()
+    }
+
+    pub fn list(&self, key: &str) -> &[String] {
+        self.fields.get(key).map(Vec::as_slice).unwrap_or_default()
+    }
+}
+
+// Strips version constraints ("glibc>=2.38") and optdepends descriptions
+// ("foo: for bar") down to the bare package or provision name.
+fn dependency_name(value: &str) -> &str {
+    let value = value.split(':').next().unwrap_or(value);
+    let end = value.find(['<', '>', '=']).unwrap_or(value.len());
+    value[..end].trim()
 }
 
-pub fn pacman_info() -> PacmanInfo {
-    let output = run_command_optional("pacman", &["-Qe"]).unwrap_or_default();
-    let explicit = output
+// `pacman -Sl` prints "repo name version [installed]" for every sync package,
+// in pacman.conf order; like pacman, the first repo providing a name wins.
+fn sync_repositories() -> HashMap<String, String> {
+    let mut repositories = HashMap::new();
+    for line in run_command_optional("pacman", &["-Sl"])
+        .unwrap_or_default()
         .lines()
-        .map(str::trim)
-        .filter(|line| !line.is_empty())
-        .map(String::from)
+    {
+        let mut parts = line.split_whitespace();
+        if let (Some(repo), Some(name)) = (parts.next(), parts.next()) {
+            repositories
+                .entry(name.to_string())
+                .or_insert_with(|| repo.to_string());
+        }
+    }
+    repositories
+}
+
+pub fn pacman_info(transaction_limit: usize) -> PacmanInfo {
+    let descs: Vec<PacmanDesc> = list_dir_sorted(PACMAN_LOCAL_DB)
+        .into_iter()
+        .filter_map(|dir| read_file_optional(dir.join("desc")))
+        .map(|text| PacmanDesc::parse(&text))
+        .filter(|desc| desc.first("NAME").is_some())
+        .collect();
+
+    let mut required = HashSet::new();
+    let mut optionally_required = HashSet::new();
+    for desc in &descs {
+        required.extend(desc.list("DEPENDS").iter().map(|d| dependency_name(d)));
+        optionally_required.extend(desc.list("OPTDEPENDS").iter().map(|d| dependency_name(d)));
+    }
+    let repositories = if descs.is_empty() {
+        HashMap::new()
+    } else {
+        sync_repositories()
+    };
+
+    let packages: Vec<PacmanPackageInfo> = descs
+        .iter()
+        .map(|desc| {
+            let name = desc.first("NAME").unwrap_or_default();
+            let names: Vec<&str> = std::iter::once(name.as_str())
+                .chain(desc.list("PROVIDES").iter().map(|p| dependency_name(p)))
+                .collect();
+            let is_required = names.iter().any(|n| required.contains(n));
+            let is_optional = names.iter().any(|n| optionally_required.contains(n));
+            // A missing %REASON% means explicitly installed; 1 means dependency.
+            let dependency = desc.first("REASON").as_deref() == Some("1");
+            let repository = repositories.get(&name).cloned();
+            PacmanPackageInfo {
+                version: desc.first("VERSION").unwrap_or_default(),
+                arch: desc.first("ARCH"),
+                reason: if dependency { "dependency" } else { "explicit" }.to_string(),
+                install_date: desc.first("INSTALLDATE").and_then(|v| v.parse().ok()),
+                size_bytes: desc.first("SIZE").and_then(|v| v.parse().ok()),
+                foreign: !repositories.is_empty() && repository.is_none(),
+                orphan: dependency && !is_required && !is_optional,
+                optional_only: dependency && !is_required && is_optional,
+                repository,
+                name,
+            }
+        })
+        .collect();
+
+    let explicit: Vec<String> = packages
+        .iter()
+        .filter(|pkg| pkg.reason == "explicit")
+        .map(|pkg| format!("{} {}", pkg.name, pkg.version))
         .collect();
-    PacmanInfo { explicit }
+    PacmanInfo {
+        package_count: packages.len(),
+        explicit_count: explicit.len(),
+        foreign_count: packages.iter().filter(|pkg| pkg.foreign).count(),
+        orphan_count: packages.iter().filter(|pkg| pkg.orphan).count(),
+        explicit,
+        packages,
+        config: pacman_config_info(),
+        log: pacman_log_info(transaction_limit),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DESC_FIXTURE: &str = "\
+%NAME%
+linux
+
+%VERSION%
+6.5.4.arch2-1
+
+%DEPENDS%
+coreutils
+kmod
+mkinitcpio>=0.7
+
+%OPTDEPENDS%
+wireless-regdb: to set the correct wireless channels of your country
+
+%REASON%
+0
+";
+
+    #[test]
+    fn parses_desc_sections() {
+        let desc = PacmanDesc::parse(DESC_FIXTURE);
+        assert_eq!(desc.first("NAME").as_deref(), Some("linux"));
+        assert_eq!(desc.first("VERSION").as_deref(), Some("6.5.4.arch2-1"));
+        assert_eq!(
+            desc.list("DEPENDS"),
+            ["coreutils", "kmod", "mkinitcpio>=0.7"]
+        );
+        assert_eq!(desc.first("REASON").as_deref(), Some("0"));
+        assert!(desc.list("PROVIDES").is_empty());
+        let deps: Vec<&str> = desc
+            .list("DEPENDS")
+            .iter()
+            .chain(desc.list("OPTDEPENDS"))
+            .map(|dep| dependency_name(dep))
+            .collect();
+        assert_eq!(deps, ["coreutils", "kmod", "mkinitcpio", "wireless-regdb"]);
+    }
 }
@@ -0,0 +1,226 @@
+use std::fs;
+
+use serde::Serialize;
+
+const PACMAN_LOG: &str = "/var/log/pacman.log";
+
+#[derive(Serialize)]
+pub struct PacmanLogInfo {
+    path: String,
+    transaction_count: usize,
+    last_full_upgrade: Option<String>,
+    transactions: Vec<PacmanTransactionInfo>,
+}
+
+#[derive(Serialize)]
+pub struct PacmanTransactionInfo {
+    started: String,
+    finished: Option<String>,
+    status: String,
+    command: Option<String>,
+    full_upgrade: bool,
+    changes: Vec<PacmanChangeInfo>,
+    warnings: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct PacmanChangeInfo {
+    action: String,
+    name: String,
+    old_version: Option<String>,
+    new_version: Option<String>,
+}
+
+// "[2023-09-20T10:15:32+0200] [ALPM] upgraded linux (6.5.3-1 -> 6.5.4-1)"; older
+// logs use "[2019-01-01 10:00]" timestamps and may lack the source tag.
+fn split_log_line(line: &str) -> Option<(&str, &str, &str)> {
+    let rest = line.strip_prefix('[')?;
+    let (timestamp, rest) = rest.split_once("] ")?;
+    let (source, message) = match rest.strip_prefix('[').and_then(|r| r.split_once("] ")) {
+        Some((source, message)) => (source, message),
+        None => ("", rest),
+    };
+    Some((timestamp, source, message))
+}
+
+fn parse_change(message: &str) -> Option<PacmanChangeInfo> {
+    let (action, rest) = message.split_once(' ')?;
+    if !matches!(
+        action,
+        "installed" | "upgraded" | "downgraded" | "reinstalled" | "removed"
+    ) {
+        return None;
+    }
+    let (name, versions) = rest.split_once(" (")?;
+    let versions = versions.strip_suffix(')')?;
+    let (old_version, new_version) = match versions.split_once(" -> ") {
+        Some((old, new)) => (Some(old.to_string()), Some(new.to_string())),
+        None if action == "removed" => (Some(versions.to_string()), None),
+        None => (None, Some(versions.to_string())),
+    };
+    Some(PacmanChangeInfo {
+        action: action.to_string(),
+        name: name.to_string(),
+        old_version,
+        new_version,
+    })
+}
+
+fn is_warning(message: &str) -> bool {
+    let lower = message.to_ascii_lowercase();
+    lower.starts_with("warning:")
+        || lower.starts_with("error:")
+        || lower.starts_with("==> warning:")
+        || lower.starts_with("==> error:")
+}
+
+fn parse_pacman_log(text: &str) -> Vec<PacmanTransactionInfo> {
+    let mut transactions = Vec::new();
+    let mut current: Option<PacmanTransactionInfo> = None;
+    let mut command: Option<String> = None;
+    let mut full_upgrade = false;
+    for line in text.lines() {
+        let Some((timestamp, source, message)) = split_log_line(line) else {
+            continue;
+        };
+        if source == "PACMAN" {
+            if let Some(cmd) = message.strip_prefix("Running '") {
+                command = Some(cmd.trim_end_matches('\'').to_string());
+                full_upgrade = false;
+            } else if message == "starting full system upgrade" {
+                full_upgrade = true;
+            }
+            continue;
+        }
+        if message == "transaction started" {
+            // A new start without a completion means the previous one was cut short.
+            transactions.extend(current.take());
+            current = Some(PacmanTransactionInfo {
+                started: timestamp.to_string(),
+                finished: None,
+                status: "incomplete".to_string(),
+                command: command.clone(),
+                full_upgrade,
+                changes: Vec::new(),
+                warnings: Vec::new(),
+            });
+            continue;
+        }
+        let Some(transaction) = current.as_mut() else {
+            continue;
+        };
+        let status = match message {
+            "transaction completed" => Some("completed"),
+            "transaction failed" => Some("failed"),
+            "transaction interrupted" => Some("interrupted"),
+            _ => None,
+        };
+        if let Some(status) = status {
+            transaction.finished = Some(timestamp.to_string());
+            transaction.status = status.to_string();
+            transactions.extend(current.take());
+        } else if let Some(change) = parse_change(message) {
+            transaction.changes.push(change);
+        } else if is_warning(message) {
+            transaction.warnings.push(message.to_string());
+        }
+    }
+    transactions.extend(current);
+    transactions
+}
+
+pub fn pacman_log_info(limit: usize) -> Option<PacmanLogInfo> {
+    // Scriptlet output can put non-UTF-8 bytes in the log; keep the rest readable.
+    let data = fs::read(PACMAN_LOG).ok().filter(|data| !data.is_empty())?;
+    let text = String::from_utf8_lossy(&data);
+    let mut transactions = parse_pacman_log(&text);
+    let last_full_upgrade = transactions
+        .iter()
+        .rev()
+        .find(|t| t.full_upgrade && t.status == "completed")
+        .and_then(|t| t.finished.clone());
+    let transaction_count = transactions.len();
+    transactions.drain(..transaction_count.saturating_sub(limit));
+    Some(PacmanLogInfo {
+        path: PACMAN_LOG.to_string(),
+        transaction_count,
+        last_full_upgrade,
+        transactions,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PACMAN_LOG_FIXTURE: &str = "\
+[2023-09-20T10:15:20+0200] [PACMAN] Running 'pacman -Syu'
+[2023-09-20T10:15:20+0200] [PACMAN] synchronizing package lists
+[2023-09-20T10:15:21+0200] [PACMAN] starting full system upgrade
+[2023-09-20T10:15:30+0200] [ALPM] transaction started
+[2023-09-20T10:15:31+0200] [ALPM] upgraded linux (6.5.3.arch1-1 -> 6.5.4.arch2-1)
+[2023-09-20T10:15:31+0200] [ALPM] installed libfoo (1.2-1)
+[2023-09-20T10:15:32+0200] [ALPM] removed oldpkg (0.9-3)
+[2023-09-20T10:15:33+0200] [ALPM-SCRIPTLET] ==> WARNING: Possibly missing firmware for module: qla2xxx
+[2023-09-20T10:15:35+0200] [ALPM] transaction completed
+[2023-09-21T08:00:00+0200] [PACMAN] Running 'pacman -S vim'
+[2023-09-21T08:00:05+0200] [ALPM] transaction started
+[2023-09-21T08:00:06+0200] [ALPM] installed vim (9.0.1897-1)
+[2019-01-01 10:00] [ALPM] transaction started
+[2019-01-01 10:00] warning: /etc/pacman.conf installed as /etc/pacman.conf.pacnew
+[2019-01-01 10:01] [ALPM] transaction failed
+";
+
+    #[test]
+    fn parses_transactions() {
+        let transactions = parse_pacman_log(PACMAN_LOG_FIXTURE);
+        assert_eq!(transactions.len(), 3);
+
+        let upgrade = &transactions[0];
+        assert_eq!(upgrade.status, "completed");
+        assert_eq!(upgrade.command.as_deref(), Some("pacman -Syu"));
+        assert!(upgrade.full_upgrade);
+        assert_eq!(
+            upgrade.finished.as_deref(),
+            Some("2023-09-20T10:15:35+0200")
+        );
+        let changes: Vec<(&str, &str, Option<&str>, Option<&str>)> = upgrade
+            .changes
+            .iter()
+            .map(|c| {
+                (
+                    c.action.as_str(),
+                    c.name.as_str(),
+                    c.old_version.as_deref(),
+                    c.new_version.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            changes,
+            [
+                (
+                    "upgraded",
+                    "linux",
+                    Some("6.5.3.arch1-1"),
+                    Some("6.5.4.arch2-1")
+                ),
+                ("installed", "libfoo", None, Some("1.2-1")),
+                ("removed", "oldpkg", Some("0.9-3"), None),
+            ]
+        );
+        assert_eq!(upgrade.warnings.len(), 1);
+
+        // Restarted without a completion line.
+        let install = &transactions[1];
+        assert_eq!(install.status, "incomplete");
+        assert_eq!(install.finished, None);
+        assert!(!install.full_upgrade);
+        assert_eq!(install.command.as_deref(), Some("pacman -S vim"));
+
+        let old = &transactions[2];
+        assert_eq!(old.status, "failed");
+        assert_eq!(old.started, "2019-01-01 10:00");
+        assert_eq!(old.warnings.len(), 1);
+    }
+}
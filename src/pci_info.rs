@@ -23,28 +23,35 @@ pub struct PciBusInfo {
     kernel_modules: Vec<String>,
 }
 
+impl PciBusInfo {
+    pub fn slot(&self) -> &str {
+        &self.slot
+    }
+
+    pub fn device_description(&self) -> &str {
+        &self.device_description
+    }
+}
+
 fn parse_named_ids(value: &str) -> (String, Option<String>, Option<String>) {
     let mut name = value.trim().to_string();
     let mut vendor_id = None;
     let mut device_id = None;
-    if let Some(lb) = value.rfind('[') {
-        if let Some(rb) = value[lb + 1..].find(']') {
-            let ids = &value[lb + 1..lb + 1 + rb];
-            if let Some((v, d)) = ids.split_once(':') {
-                vendor_id = Some(v.to_string());
-                device_id = Some(d.to_string());
-            }
-            name = value[..lb].trim_end().to_string();
+    if let Some(lb) = value.rfind('[')
+        && let Some(rb) = value[lb + 1..].find(']')
+    {
+        let ids = &value[lb + 1..lb + 1 + rb];
+        if let Some((v, d)) = ids.split_once(':') {
+            vendor_id = Some(v.to_string());
+            device_id = Some(d.to_string());
         }
+        name = value[..lb].trim_end().to_string();
     }
     (name, vendor_id, device_id)
 }
 
 fn parse_pci_header(line: &str) -> PciBusInfo {
-    let (slot, rest) = line
-        .split_once(' ')
-        .map(|(s, r)| (s, r))
-        .unwrap_or((line, ""));
+    let (slot, rest) = line.split_once(' ').unwrap_or((line, ""));
     let (class_part, after_class) = if let Some(pos) = rest.find("]: ") {
         (&rest[..pos + 1], &rest[pos + 3..])
     } else {
@@ -60,26 +67,26 @@ fn parse_pci_header(line: &str) -> PciBusInfo {
 
     let mut after = after_class.trim().to_string();
     let mut revision = None;
-    if let Some(rev_start) = after.rfind("(rev ") {
-        if after.ends_with(')') {
-            let rev = after[rev_start + 5..after.len() - 1].trim().to_string();
-            revision = Some(rev);
-            after = after[..rev_start].trim_end().to_string();
-        }
+    if let Some(rev_start) = after.rfind("(rev ")
+        && after.ends_with(')')
+    {
+        let rev = after[rev_start + 5..after.len() - 1].trim().to_string();
+        revision = Some(rev);
+        after = after[..rev_start].trim_end().to_string();
     }
 
     let mut vendor_id = String::new();
     let mut device_id = String::new();
     let mut device_description = after.trim().to_string();
-    if let Some(lb) = after.rfind('[') {
-        if let Some(rb) = after[lb + 1..].find(']') {
-            let ids = &after[lb + 1..lb + 1 + rb];
-            if let Some((v, d)) = ids.split_once(':') {
-                vendor_id = v.to_string();
-                device_id = d.to_string();
-            }
-            device_description = after[..lb].trim_end().to_string();
+    if let Some(lb) = after.rfind('[')
+        && let Some(rb) = after[lb + 1..].find(']')
+    {
+        let ids = &after[lb + 1..lb + 1 + rb];
+        if let Some((v, d)) = ids.split_once(':') {
+            vendor_id = v.to_string();
+            device_id = d.to_string();
         }
+        device_description = after[..lb].trim_end().to_string();
     }
 
     PciBusInfo {
@@ -9,6 +9,16 @@ pub struct ProcInfo {
     version: String,
 }
 
+impl ProcInfo {
+    pub fn cmdline(&self) -> &str {
+        &self.cmdline
+    }
+
+    pub fn mem_total_kib(&self) -> Option<u64> {
+        self.meminfo.mem_total.split_whitespace().next()?.parse().ok()
+    }
+}
+
 #[derive(Serialize)]
 pub struct ProcMemInfo {
     mem_total: String,
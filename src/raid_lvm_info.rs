@@ -0,0 +1,325 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::str::FromStr;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::lsblk_info::{block_device_for_path, BlockDeviceInfo};
+use crate::utils::{
+    json_str, list_dir_sorted, read_file_optional, read_file_string, run_command_optional,
+};
+
+#[derive(Serialize)]
+pub struct RaidLvmInfo {
+    md_personalities: Vec<String>,
+    md_arrays: Vec<MdArrayInfo>,
+    lvm: Option<LvmInfo>,
+}
+
+#[derive(Serialize)]
+pub struct MdArrayInfo {
+    name: String,
+    path: String,
+    lsblk_path: Option<String>,
+    level: Option<String>,
+    state: Option<String>,
+    raid_disks: Option<u64>,
+    degraded: Option<u64>,
+    sync_action: Option<String>,
+    sync_progress_percent: Option<f64>,
+    mdstat_status: Option<String>,
+    members: Vec<MdMemberInfo>,
+}
+
+#[derive(Serialize)]
+pub struct MdMemberInfo {
+    name: String,
+    path: String,
+    lsblk_path: Option<String>,
+    slot: Option<String>,
+    state: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct LvmInfo {
+    vgs: Vec<LvmVgInfo>,
+    pvs: Vec<LvmPvInfo>,
+    lvs: Vec<LvmLvInfo>,
+}
+
+#[derive(Serialize)]
+pub struct LvmVgInfo {
+    name: String,
+    attr: String,
+    size_bytes: Option<u64>,
+    free_bytes: Option<u64>,
+    pv_count: Option<u64>,
+    lv_count: Option<u64>,
+}
+
+#[derive(Serialize)]
+pub struct LvmPvInfo {
+    path: String,
+    lsblk_path: Option<String>,
+    vg_name: String,
+    attr: String,
+    size_bytes: Option<u64>,
+    free_bytes: Option<u64>,
+}
+
+#[derive(Serialize)]
+pub struct LvmLvInfo {
+    name: String,
+    vg_name: String,
+    path: String,
+    dm_path: String,
+    lsblk_path: Option<String>,
+    attr: String,
+    lv_type: String,
+    size_bytes: Option<u64>,
+    pool_lv: Option<String>,
+    origin: Option<String>,
+    data_percent: Option<f64>,
+    metadata_percent: Option<f64>,
+}
+
+// Redundant arrays have a "[2/2] [UU]" member status after the size; raid0 and
+// linear arrays have none.
+fn mdstat_status(line: &str) -> Option<String> {
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    tokens.windows(2).find_map(|pair| {
+        let counts = pair[0].strip_prefix('[')?.strip_suffix(']')?;
+        let (active, total) = counts.split_once('/')?;
+        let flags = pair[1].strip_prefix('[')?.strip_suffix(']')?;
+        let is_count = |value: &str| !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit());
+        (is_count(active) && is_count(total) && flags.bytes().all(|b| b == b'U' || b == b'_'))
+            .then(|| format!("{} {}", pair[0], pair[1]))
+    })
+}
+
+fn parse_mdstat(text: &str) -> (Vec<String>, HashMap<String, String>) {
+    let mut personalities = Vec::new();
+    let mut statuses = HashMap::new();
+    let mut current: Option<String> = None;
+    for line in text.lines() {
+        if let Some(rest) = line.strip_prefix("Personalities :") {
+            personalities = rest
+                .split_whitespace()
+                .map(|p| p.trim_matches(|c| c == '[' || c == ']').to_string())
+                .collect();
+            continue;
+        }
+        if !line.starts_with(char::is_whitespace) {
+            current = line
+                .split_once(" : ")
+                .map(|(name, _)| name.trim().to_string())
+                .filter(|name| name.starts_with("md"));
+            continue;
+        }
+        if let Some(name) = &current
+            && !statuses.contains_key(name)
+            && let Some(status) = mdstat_status(line)
+        {
+            statuses.insert(name.clone(), status);
+        }
+    }
+    (personalities, statuses)
+}
+
+fn lsblk_path(path: &str, devices: &[BlockDeviceInfo]) -> Option<String> {
+    block_device_for_path(path, devices).map(|dev| dev.path().to_string())
+}
+
+fn md_members(md_dir: &Path, devices: &[BlockDeviceInfo]) -> Vec<MdMemberInfo> {
+    list_dir_sorted(md_dir)
+        .into_iter()
+        .filter_map(|dir| {
+            let dir_name = dir.file_name()?.to_string_lossy().into_owned();
+            let name = dir_name.strip_prefix("dev-")?.to_string();
+            let path = format!("/dev/{name}");
+            Some(MdMemberInfo {
+                lsblk_path: lsblk_path(&path, devices),
+                path,
+                name,
+                slot: read_file_optional(dir.join("slot")),
+                state: read_file_optional(dir.join("state")),
+            })
+        })
+        .collect()
+}
+
+fn sync_progress(value: &str) -> Option<f64> {
+    let (done, total) = value.split_once('/')?;
+    let done: f64 = done.trim().parse().ok()?;
+    let total: f64 = total.trim().parse().ok()?;
+    (total > 0.0).then(|| (done / total * 1000.0).round() / 10.0)
+}
+
+fn md_arrays(statuses: &HashMap<String, String>, devices: &[BlockDeviceInfo]) -> Vec<MdArrayInfo> {
+    list_dir_sorted("/sys/block")
+        .into_iter()
+        .filter(|path| path.join("md").is_dir())
+        .filter_map(|path| {
+            let name = path.file_name()?.to_string_lossy().into_owned();
+            let md_dir = path.join("md");
+            let read = |file: &str| read_file_optional(md_dir.join(file));
+            let path = format!("/dev/{name}");
+            Some(MdArrayInfo {
+                lsblk_path: lsblk_path(&path, devices),
+                path,
+                level: read("level"),
+                state: read("array_state"),
+                raid_disks: read("raid_disks").and_then(|v| v.parse().ok()),
+                degraded: read("degraded").and_then(|v| v.parse().ok()),
+                sync_action: read("sync_action"),
+                sync_progress_percent: read("sync_completed").as_deref().and_then(sync_progress),
+                mdstat_status: statuses.get(&name).cloned(),
+                members: md_members(&md_dir, devices),
+                name,
+            })
+        })
+        .collect()
+}
+
+// LVM's JSON report gives every field, numbers included, as a string.
+fn lvm_text(row: &Value, pointer: &str) -> String {
+    json_str(row, pointer).unwrap_or_default()
+}
+
+fn lvm_number<T: FromStr>(row: &Value, pointer: &str) -> Option<T> {
+    json_str(row, pointer)?.parse().ok()
+}
+
+fn dm_path(vg: &str, lv: &str) -> String {
+    format!(
+        "/dev/mapper/{}-{}",
+        vg.replace('-', "--"),
+        lv.replace('-', "--")
+    )
+}
+
+fn lv_type(attr: &str) -> &'static str {
+    match attr.chars().next() {
+        Some('t') => "thin-pool",
+        Some('V') => "thin",
+        Some('s') | Some('S') => "snapshot",
+        Some('o') => "origin",
+        Some('m') | Some('M') => "mirror",
+        Some('r') | Some('R') => "raid",
+        Some('C') => "cache",
+        Some('p') => "pvmove",
+        Some('v') => "virtual",
+        _ => "linear",
+    }
+}
+
+fn parse_lvm_fullreport(output: &str, devices: &[BlockDeviceInfo]) -> Option<LvmInfo> {
+    let parsed: Value = serde_json::from_str(output).ok()?;
+    let reports = parsed.get("report")?.as_array()?;
+    let rows = |key: &str| -> Vec<&Value> {
+        reports
+            .iter()
+            .filter_map(|report| report.get(key)?.as_array())
+            .flatten()
+            .collect()
+    };
+    let vgs = rows("vg")
+        .into_iter()
+        .map(|vg| LvmVgInfo {
+            name: lvm_text(vg, "/vg_name"),
+            attr: lvm_text(vg, "/vg_attr"),
+            size_bytes: lvm_number(vg, "/vg_size"),
+            free_bytes: lvm_number(vg, "/vg_free"),
+            pv_count: lvm_number(vg, "/pv_count"),
+            lv_count: lvm_number(vg, "/lv_count"),
+        })
+        .collect();
+    let pvs = rows("pv")
+        .into_iter()
+        .map(|pv| {
+            let path = lvm_text(pv, "/pv_name");
+            LvmPvInfo {
+                lsblk_path: lsblk_path(&path, devices),
+                path,
+                vg_name: lvm_text(pv, "/vg_name"),
+                attr: lvm_text(pv, "/pv_attr"),
+                size_bytes: lvm_number(pv, "/pv_size"),
+                free_bytes: lvm_number(pv, "/pv_free"),
+            }
+        })
+        .collect();
+    let lvs = rows("lv")
+        .into_iter()
+        .map(|lv| {
+            let name = lvm_text(lv, "/lv_name");
+            let vg_name = lvm_text(lv, "/vg_name");
+            let attr = lvm_text(lv, "/lv_attr");
+            let dm_path = json_str(lv, "/lv_dm_path").unwrap_or_else(|| dm_path(&vg_name, &name));
+            LvmLvInfo {
+                path: json_str(lv, "/lv_path").unwrap_or_else(|| format!("/dev/{vg_name}/{name}")),
+                lsblk_path: lsblk_path(&dm_path, devices),
+                dm_path,
+                lv_type: lv_type(&attr).to_string(),
+                size_bytes: lvm_number(lv, "/lv_size"),
+                pool_lv: json_str(lv, "/pool_lv"),
+                origin: json_str(lv, "/origin"),
+                data_percent: lvm_number(lv, "/data_percent"),
+                metadata_percent: lvm_number(lv, "/metadata_percent"),
+                name,
+                vg_name,
+                attr,
+            }
+        })
+        .collect();
+    Some(LvmInfo { vgs, pvs, lvs })
+}
+
+pub fn raid_lvm_info(devices: &[BlockDeviceInfo]) -> RaidLvmInfo {
+    let (md_personalities, statuses) = parse_mdstat(&read_file_string("/proc/mdstat"));
+    let lvm = run_command_optional(
+        "lvm",
+        &[
+            "fullreport",
+            "--reportformat",
+            "json",
+            "--units",
+            "b",
+            "--nosuffix",
+        ],
+    )
+    .and_then(|output| parse_lvm_fullreport(&output, devices));
+    RaidLvmInfo {
+        md_personalities,
+        md_arrays: md_arrays(&statuses, devices),
+        lvm,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MDSTAT_FIXTURE: &str = "\
+Personalities : [raid0] [raid1] [linear]
+md1 : active raid1 sdb2[1] sda2[0]
+      976630336 blocks super 1.2 [2/1] [U_]
+      [=>...................]  recovery =  8.5% (83456/976630336) finish=10.0min
+      bitmap: 2/8 pages [8KB], 65536KB chunk
+
+md0 : active raid0 sdd1[1] sdc1[0]
+      1953260544 blocks super 1.2 512k chunks
+      bitmap: 0/8 pages [0KB], 65536KB chunk
+
+unused devices: <none>
+";
+
+    #[test]
+    fn parses_mdstat_statuses() {
+        let (personalities, statuses) = parse_mdstat(MDSTAT_FIXTURE);
+        assert_eq!(personalities, ["raid0", "raid1", "linear"]);
+        assert_eq!(statuses.get("md1").map(String::as_str), Some("[2/1] [U_]"));
+        // raid0 has no member status; the bitmap line must not be taken for one.
+        assert_eq!(statuses.get("md0"), None);
+    }
+}
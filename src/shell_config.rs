@@ -0,0 +1,480 @@
+use std::collections::HashMap;
+
+#[derive(Clone)]
+pub enum ShellValue {
+    Scalar(String),
+    Array(Vec<String>),
+}
+
+#[derive(Clone, Default)]
+pub struct ShellVars {
+    values: HashMap<String, ShellValue>,
+}
+
+impl ShellVars {
+    pub fn parse(&mut self, text: &str) {
+        let chars: Vec<char> = text.chars().collect();
+        let mut pos = 0usize;
+        loop {
+            skip_separators(&chars, &mut pos);
+            let Some(&c) = chars.get(pos) else {
+                break;
+            };
+            if c == '#' {
+                skip_line(&chars, &mut pos);
+                continue;
+            }
+            skip_declaration_keywords(&chars, &mut pos);
+            let start = pos;
+            let name = read_identifier(&chars, &mut pos);
+            let append = if name.is_empty() {
+                None
+            } else if chars.get(pos) == Some(&'=') {
+                pos += 1;
+                Some(false)
+            } else if chars.get(pos) == Some(&'+') && chars.get(pos + 1) == Some(&'=') {
+                pos += 2;
+                Some(true)
+            } else {
+                None
+            };
+            let Some(append) = append else {
+                pos = start;
+                skip_line(&chars, &mut pos);
+                continue;
+            };
+            if chars.get(pos) == Some(&'(') {
+                pos += 1;
+                let items = self.read_array(&chars, &mut pos);
+                self.assign_array(name, items, append);
+            } else {
+                // A scalar assignment joins "${ARRAY[@]}" back into one string.
+                let (words, _) = self.read_word(&chars, &mut pos);
+                self.assign_scalar(name, words.join(" "), append);
+            }
+        }
+    }
+
+    pub fn scalar(&self, name: &str) -> Option<String> {
+        match self.values.get(name)? {
+            ShellValue::Scalar(value) => Some(value.clone()),
+            ShellValue::Array(items) => Some(items.join(" ")),
+        }
+    }
+
+    pub fn non_empty_scalar(&self, name: &str) -> Option<String> {
+        self.scalar(name).filter(|value| !value.is_empty())
+    }
+
+    pub fn array(&self, name: &str) -> Vec<String> {
+        match self.values.get(name) {
+            Some(ShellValue::Array(items)) => items.clone(),
+            Some(ShellValue::Scalar(value)) => value.split_whitespace().map(String::from).collect(),
+            None => Vec::new(),
+        }
+    }
+
+    fn assign_scalar(&mut self, name: String, value: String, append: bool) {
+        if append {
+            match self.values.get_mut(&name) {
+                Some(ShellValue::Scalar(existing)) => {
+                    existing.push_str(&value);
+                    return;
+                }
+                Some(ShellValue::Array(items)) => {
+                    match items.first_mut() {
+                        Some(first) => first.push_str(&value),
+                        None => items.push(value),
+                    }
+                    return;
+                }
+                None => {}
+            }
+        }
+        self.values.insert(name, ShellValue::Scalar(value));
+    }
+
+    fn assign_array(&mut self, name: String, items: Vec<String>, append: bool) {
+        if append {
+            match self.values.get_mut(&name) {
+                Some(ShellValue::Array(existing)) => {
+                    existing.extend(items);
+                    return;
+                }
+                Some(ShellValue::Scalar(existing)) => {
+                    let mut merged = vec![existing.clone()];
+                    merged.extend(items);
+                    self.values.insert(name, ShellValue::Array(merged));
+                    return;
+                }
+                None => {}
+            }
+        }
+        self.values.insert(name, ShellValue::Array(items));
+    }
+
+    fn read_array(&self, chars: &[char], pos: &mut usize) -> Vec<String> {
+        let mut items = Vec::new();
+        loop {
+            while let Some(&c) = chars.get(*pos) {
+                if c.is_whitespace() {
+                    *pos += 1;
+                } else if c == '\\' && chars.get(*pos + 1) == Some(&'\n') {
+                    *pos += 2;
+                } else {
+                    break;
+                }
+            }
+            match chars.get(*pos) {
+                None => break,
+                Some(')') => {
+                    *pos += 1;
+                    break;
+                }
+                Some('#') => skip_comment(chars, pos),
+                Some(_) => {
+                    let before = *pos;
+                    let (words, unquoted_expansion) = self.read_word(chars, pos);
+                    if *pos == before {
+                        // Stray separator such as ';' inside the list.
+                        *pos += 1;
+                        continue;
+                    }
+                    if unquoted_expansion {
+                        for word in words {
+                            items.extend(word.split_whitespace().map(String::from));
+                        }
+                    } else {
+                        items.extend(words);
+                    }
+                }
+            }
+        }
+        items
+    }
+
+    // Returns the fields one shell word expands to: usually one, but a quoted
+    // "${ARRAY[@]}" yields one field per element (and none for an empty array).
+    fn read_word(&self, chars: &[char], pos: &mut usize) -> (Vec<String>, bool) {
+        let mut fields = Vec::new();
+        let mut out = String::new();
+        let mut started = false;
+        let mut unquoted_expansion = false;
+        while let Some(&c) = chars.get(*pos) {
+            match c {
+                c if c.is_whitespace() => break,
+                ';' | '(' | ')' | '&' | '|' => break,
+                '\'' => {
+                    started = true;
+                    *pos += 1;
+                    while let Some(&c) = chars.get(*pos) {
+                        *pos += 1;
+                        if c == '\'' {
+                            break;
+                        }
+                        out.push(c);
+                    }
+                }
+                '"' => {
+                    *pos += 1;
+                    let mut array_expanded = false;
+                    while let Some(&c) = chars.get(*pos) {
+                        match c {
+                            '"' => {
+                                *pos += 1;
+                                break;
+                            }
+                            '\\' => {
+                                *pos += 1;
+                                match chars.get(*pos) {
+                                    Some('\n') => *pos += 1,
+                                    Some(&next @ ('$' | '`' | '"' | '\\')) => {
+                                        out.push(next);
+                                        *pos += 1;
+                                    }
+                                    _ => out.push('\\'),
+                                }
+                            }
+                            '$' => match self.quoted_array_expansion(chars, pos) {
+                                Some(items) => {
+                                    array_expanded = true;
+                                    let mut items = items.into_iter();
+                                    if let Some(first) = items.next() {
+                                        out.push_str(&first);
+                                        started = true;
+                                    }
+                                    for item in items {
+                                        fields.push(std::mem::replace(&mut out, item));
+                                    }
+                                }
+                                None => out.push_str(&self.expand(chars, pos)),
+                            },
+                            '`' => skip_backticks(chars, pos),
+                            _ => {
+                                out.push(c);
+                                *pos += 1;
+                            }
+                        }
+                    }
+                    // "" is an empty word, "${EMPTY[@]}" is no word at all.
+                    started |= !array_expanded;
+                }
+                '\\' => {
+                    *pos += 1;
+                    match chars.get(*pos) {
+                        Some('\n') => *pos += 1,
+                        Some(&next) => {
+                            out.push(next);
+                            started = true;
+                            *pos += 1;
+                        }
+                        None => {}
+                    }
+                }
+                '$' => {
+                    out.push_str(&self.expand(chars, pos));
+                    started = true;
+                    unquoted_expansion = true;
+                }
+                '`' => skip_backticks(chars, pos),
+                _ => {
+                    out.push(c);
+                    started = true;
+                    *pos += 1;
+                }
+            }
+        }
+        if started {
+            fields.push(out);
+        }
+        (fields, unquoted_expansion)
+    }
+
+    // Matches "${NAME[@]}" at `pos` (pointing at the '$') and returns the elements.
+    fn quoted_array_expansion(&self, chars: &[char], pos: &mut usize) -> Option<Vec<String>> {
+        if chars.get(*pos + 1) != Some(&'{') {
+            return None;
+        }
+        let mut end = *pos + 2;
+        let name = read_identifier(chars, &mut end);
+        let suffix: String = chars.get(end..end + 4)?.iter().collect();
+        if name.is_empty() || suffix != "[@]}" {
+            return None;
+        }
+        *pos = end + 4;
+        Some(self.array(&name))
+    }
+
+    fn expand(&self, chars: &[char], pos: &mut usize) -> String {
+        // `pos` points at the '$'.
+        *pos += 1;
+        match chars.get(*pos) {
+            Some('{') => {
+                *pos += 1;
+                let mut inner = String::new();
+                while let Some(&c) = chars.get(*pos) {
+                    *pos += 1;
+                    if c == '}' {
+                        break;
+                    }
+                    inner.push(c);
+                }
+                self.expand_braced(&inner)
+            }
+            Some('(') => {
+                let mut depth = 0usize;
+                while let Some(&c) = chars.get(*pos) {
+                    *pos += 1;
+                    if c == '(' {
+                        depth += 1;
+                    } else if c == ')' {
+                        depth -= 1;
+                        if depth == 0 {
+                            break;
+                        }
+                    }
+                }
+                String::new()
+            }
+            _ => {
+                let name = read_identifier(chars, pos);
+                if name.is_empty() {
+                    "$".to_string()
+                } else {
+                    self.scalar_first(&name)
+                }
+            }
+        }
+    }
+
+    fn expand_braced(&self, inner: &str) -> String {
+        if let Some((name, default)) = inner.split_once(":-") {
+            let value = self.scalar_first(name);
+            return if value.is_empty() {
+                default.to_string()
+            } else {
+                value
+            };
+        }
+        if let Some(name) = inner
+            .strip_suffix("[@]")
+            .or_else(|| inner.strip_suffix("[*]"))
+        {
+            return self.scalar(name).unwrap_or_default();
+        }
+        self.scalar_first(inner)
+    }
+
+    fn scalar_first(&self, name: &str) -> String {
+        match self.values.get(name) {
+            Some(ShellValue::Scalar(value)) => value.clone(),
+            Some(ShellValue::Array(items)) => items.first().cloned().unwrap_or_default(),
+            None => String::new(),
+        }
+    }
+}
+
+fn read_identifier(chars: &[char], pos: &mut usize) -> String {
+    let mut name = String::new();
+    while let Some(&c) = chars.get(*pos) {
+        let valid = c == '_' || c.is_ascii_alphabetic() || (!name.is_empty() && c.is_ascii_digit());
+        if !valid {
+            break;
+        }
+        name.push(c);
+        *pos += 1;
+    }
+    name
+}
+
+fn skip_separators(chars: &[char], pos: &mut usize) {
+    while let Some(&c) = chars.get(*pos) {
+        if c.is_whitespace() || c == ';' {
+            *pos += 1;
+        } else {
+            break;
+        }
+    }
+}
+
+fn skip_declaration_keywords(chars: &[char], pos: &mut usize) {
+    loop {
+        let start = *pos;
+        let word = read_identifier(chars, pos);
+        let is_keyword = matches!(
+            word.as_str(),
+            "export" | "declare" | "typeset" | "local" | "readonly"
+        );
+        if !is_keyword || !chars.get(*pos).is_some_and(|c| *c == ' ' || *c == '\t') {
+            *pos = start;
+            return;
+        }
+        loop {
+            while chars.get(*pos).is_some_and(|c| *c == ' ' || *c == '\t') {
+                *pos += 1;
+            }
+            if chars.get(*pos) != Some(&'-') {
+                break;
+            }
+            while chars.get(*pos).is_some_and(|c| !c.is_whitespace()) {
+                *pos += 1;
+            }
+        }
+    }
+}
+
+fn skip_comment(chars: &[char], pos: &mut usize) {
+    while let Some(&c) = chars.get(*pos) {
+        if c == '\n' {
+            break;
+        }
+        *pos += 1;
+    }
+}
+
+fn skip_line(chars: &[char], pos: &mut usize) {
+    while let Some(&c) = chars.get(*pos) {
+        *pos += 1;
+        if c == '\\' && chars.get(*pos) == Some(&'\n') {
+            *pos += 1;
+        } else if c == '\n' {
+            break;
+        }
+    }
+}
+
+fn skip_backticks(chars: &[char], pos: &mut usize) {
+    *pos += 1;
+    while let Some(&c) = chars.get(*pos) {
+        *pos += 1;
+        if c == '`' {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> ShellVars {
+        let mut vars = ShellVars::default();
+        vars.parse(text);
+        vars
+    }
+
+    #[test]
+    fn parses_scalars_and_quoting() {
+        let vars =
+            parse("# comment\nCOMPRESSION=\"zstd\"\nexport OPTS='-v -T0' ; EMPTY=\nESC=a\\ b\n");
+        assert_eq!(vars.scalar("COMPRESSION").as_deref(), Some("zstd"));
+        assert_eq!(vars.scalar("OPTS").as_deref(), Some("-v -T0"));
+        assert_eq!(vars.scalar("EMPTY").as_deref(), Some(""));
+        assert_eq!(vars.non_empty_scalar("EMPTY"), None);
+        assert_eq!(vars.scalar("ESC").as_deref(), Some("a b"));
+        assert_eq!(vars.scalar("MISSING"), None);
+    }
+
+    #[test]
+    fn parses_multiline_arrays_with_comments_and_append() {
+        let vars = parse(
+            "HOOKS=(base udev # the basics\n  autodetect\n  'block')\nHOOKS+=(filesystems)\n",
+        );
+        assert_eq!(
+            vars.array("HOOKS"),
+            ["base", "udev", "autodetect", "block", "filesystems"]
+        );
+    }
+
+    #[test]
+    fn expands_variables() {
+        let vars = parse(
+            "A=one\nB=\"$A-two\"\nC=${MISSING:-fallback}\nARR=(x y)\nFIRST=$ARR\nJOINED=\"${ARR[@]}\"\n",
+        );
+        assert_eq!(vars.scalar("B").as_deref(), Some("one-two"));
+        assert_eq!(vars.scalar("C").as_deref(), Some("fallback"));
+        assert_eq!(vars.scalar("FIRST").as_deref(), Some("x"));
+        assert_eq!(vars.scalar("JOINED").as_deref(), Some("x y"));
+    }
+
+    #[test]
+    fn quoted_array_expansion_keeps_elements() {
+        let vars = parse(
+            "HOOKS=(base 'sd vconsole')\nHOOKS=(\"${HOOKS[@]}\" filesystems)\nNONE=()\nALSO=(\"${NONE[@]}\" fsck)\n",
+        );
+        assert_eq!(vars.array("HOOKS"), ["base", "sd vconsole", "filesystems"]);
+        assert_eq!(vars.array("ALSO"), ["fsck"]);
+    }
+
+    #[test]
+    fn unquoted_expansion_is_split() {
+        let vars = parse("MODS=\"a b\"\nMODULES=($MODS c)\n");
+        assert_eq!(vars.array("MODULES"), ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn ignores_commands() {
+        let vars = parse("if [ -f x ]; then\n  echo $(date)\nfi\nOK=1\n");
+        assert_eq!(vars.scalar("OK").as_deref(), Some("1"));
+    }
+}
@@ -0,0 +1,220 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::os::unix::fs::MetadataExt;
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::lsblk_info::{block_device_for_spec, block_device_spec_exists, BlockDeviceInfo};
+use crate::proc_info::ProcInfo;
+use crate::utils::{dev_maj_min, list_dir_sorted, read_file_optional, read_file_string};
+
+#[derive(Serialize)]
+pub struct SwapInfo {
+    swappiness: Option<String>,
+    devices: Vec<SwapDeviceInfo>,
+    zram: Vec<ZramDeviceInfo>,
+    zswap: BTreeMap<String, String>,
+    hibernation: HibernationInfo,
+}
+
+#[derive(Serialize)]
+pub struct SwapDeviceInfo {
+    path: String,
+    swap_type: String,
+    size_kib: u64,
+    used_kib: u64,
+    priority: i64,
+}
+
+#[derive(Serialize)]
+pub struct ZramDeviceInfo {
+    name: String,
+    algorithm: Option<String>,
+    disksize: Option<u64>,
+    orig_data_size: Option<u64>,
+    compr_data_size: Option<u64>,
+    mem_used_total: Option<u64>,
+    compression_ratio: Option<f64>,
+}
+
+#[derive(Serialize)]
+pub struct HibernationInfo {
+    kernel_supported: bool,
+    resume: Option<String>,
+    resume_offset: Option<String>,
+    resume_device_found: Option<bool>,
+    resume_swap: Option<String>,
+    resume_swap_kib: Option<u64>,
+    swap_total_kib: u64,
+    mem_total_kib: Option<u64>,
+    swap_covers_ram: Option<bool>,
+    viable: bool,
+}
+
+fn parse_proc_swaps(text: &str) -> Vec<SwapDeviceInfo> {
+    text.lines()
+        .skip(1)
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            Some(SwapDeviceInfo {
+                path: parts.next()?.replace("\\040", " "),
+                swap_type: parts.next()?.to_string(),
+                size_kib: parts.next()?.parse().ok()?,
+                used_kib: parts.next()?.parse().ok()?,
+                priority: parts.next()?.parse().ok()?,
+            })
+        })
+        .collect()
+}
+
+fn selected_algorithm(value: &str) -> Option<String> {
+    let start = value.find('[')? + 1;
+    let end = value[start..].find(']')? + start;
+    Some(value[start..end].to_string())
+}
+
+fn zram_device(path: &Path) -> Option<ZramDeviceInfo> {
+    let name = path.file_name()?.to_string_lossy().into_owned();
+    let mm_stat: Vec<u64> = read_file_string(path.join("mm_stat"))
+        .split_whitespace()
+        .filter_map(|value| value.parse().ok())
+        .collect();
+    let orig_data_size = mm_stat.first().copied();
+    let compr_data_size = mm_stat.get(1).copied();
+    let compression_ratio = match (orig_data_size, compr_data_size) {
+        (Some(orig), Some(compr)) if compr > 0 => {
+            Some((orig as f64 / compr as f64 * 100.0).round() / 100.0)
+        }
+        _ => None,
+    };
+    Some(ZramDeviceInfo {
+        name,
+        algorithm: read_file_optional(path.join("comp_algorithm"))
+            .as_deref()
+            .and_then(selected_algorithm),
+        disksize: read_file_optional(path.join("disksize")).and_then(|v| v.parse().ok()),
+        orig_data_size,
+        compr_data_size,
+        mem_used_total: mm_stat.get(2).copied(),
+        compression_ratio,
+    })
+}
+
+fn zswap_parameters() -> BTreeMap<String, String> {
+    list_dir_sorted("/sys/module/zswap/parameters")
+        .into_iter()
+        .filter_map(|path| {
+            let name = path.file_name()?.to_string_lossy().into_owned();
+            Some((name, read_file_optional(&path)?))
+        })
+        .collect()
+}
+
+fn cmdline_value(cmdline: &str, key: &str) -> Option<String> {
+    cmdline
+        .split_whitespace()
+        .find_map(|token| token.strip_prefix(key)?.strip_prefix('='))
+        .map(String::from)
+}
+
+// resume= names the block device holding the image: the swap partition itself
+// or, with resume_offset=, the filesystem containing the swap file.
+fn resume_maj_min(spec: &str, devices: &[BlockDeviceInfo]) -> Option<String> {
+    let is_number = |value: &str| !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit());
+    if let Some((major, minor)) = spec.split_once(':')
+        && is_number(major)
+        && is_number(minor)
+    {
+        return Some(spec.to_string());
+    }
+    block_device_for_spec(spec, devices).map(|dev| dev.maj_min().trim().to_string())
+}
+
+fn swap_maj_min(swap: &SwapDeviceInfo) -> Option<String> {
+    let metadata = fs::metadata(&swap.path).ok()?;
+    if swap.swap_type == "partition" {
+        Some(dev_maj_min(metadata.rdev()))
+    } else {
+        Some(dev_maj_min(metadata.dev()))
+    }
+}
+
+fn find_resume_swap<'a>(
+    resume: &str,
+    has_offset: bool,
+    devices: &[BlockDeviceInfo],
+    swaps: &'a [SwapDeviceInfo],
+) -> Option<&'a SwapDeviceInfo> {
+    let target = resume_maj_min(resume, devices)?;
+    swaps.iter().find(|swap| {
+        (swap.swap_type == "file") == has_offset
+            && swap_maj_min(swap).as_deref() == Some(target.as_str())
+    })
+}
+
+fn hibernation_info(
+    proc: &ProcInfo,
+    devices: &[BlockDeviceInfo],
+    swaps: &[SwapDeviceInfo],
+) -> HibernationInfo {
+    let kernel_supported = read_file_string("/sys/power/state")
+        .split_whitespace()
+        .any(|state| state == "disk");
+    let resume = cmdline_value(proc.cmdline(), "resume");
+    let resume_offset = cmdline_value(proc.cmdline(), "resume_offset");
+    let resume_device_found = resume
+        .as_deref()
+        .and_then(|spec| block_device_spec_exists(spec, devices));
+    // zram lives in RAM, so it cannot hold a hibernation image.
+    let swap_total_kib = swaps
+        .iter()
+        .filter(|swap| !swap.path.starts_with("/dev/zram"))
+        .map(|swap| swap.size_kib)
+        .sum();
+    let resume_swap = resume
+        .as_deref()
+        .and_then(|spec| find_resume_swap(spec, resume_offset.is_some(), devices, swaps));
+    let mem_total_kib = proc.mem_total_kib();
+    // Only the resume target receives the image; without one there is nothing
+    // to compare, and an inactive target cannot hold it.
+    let swap_covers_ram = match (&resume, resume_swap, mem_total_kib) {
+        (Some(_), Some(swap), Some(mem)) => Some(swap.size_kib >= mem),
+        (Some(_), None, Some(_)) => Some(false),
+        _ => None,
+    };
+    let viable =
+        kernel_supported && resume_device_found == Some(true) && swap_covers_ram == Some(true);
+    HibernationInfo {
+        kernel_supported,
+        resume,
+        resume_offset,
+        resume_device_found,
+        resume_swap: resume_swap.map(|swap| swap.path.clone()),
+        resume_swap_kib: resume_swap.map(|swap| swap.size_kib),
+        swap_total_kib,
+        mem_total_kib,
+        swap_covers_ram,
+        viable,
+    }
+}
+
+pub fn swap_info(proc: &ProcInfo, devices: &[BlockDeviceInfo]) -> SwapInfo {
+    let swaps = parse_proc_swaps(&read_file_string("/proc/swaps"));
+    let zram = list_dir_sorted("/sys/block")
+        .into_iter()
+        .filter(|path| {
+            path.file_name()
+                .is_some_and(|name| name.to_string_lossy().starts_with("zram"))
+        })
+        .filter_map(|path| zram_device(&path))
+        .collect();
+    let hibernation = hibernation_info(proc, devices, &swaps);
+    SwapInfo {
+        swappiness: read_file_optional("/proc/sys/vm/swappiness"),
+        devices: swaps,
+        zram,
+        zswap: zswap_parameters(),
+        hibernation,
+    }
+}
@@ -1,36 +1,431 @@
+use std::collections::BTreeSet;
+
 use serde::Serialize;
 
-use crate::utils::{current_username, run_command_string};
+use crate::utils::{
+    current_username, list_dir_sorted, read_file_optional, read_file_string, run_command_optional,
+    run_command_string,
+};
+
+const SUDOERS: &str = "/etc/sudoers";
+const POLKIT_RULES_DIRS: [&str; 2] = ["/etc/polkit-1/rules.d", "/usr/share/polkit-1/rules.d"];
+const POLKIT_LOCALAUTHORITY_DIR: &str = "/etc/polkit-1/localauthority.conf.d";
+const ADMIN_GROUPS: [&str; 3] = ["wheel", "sudo", "admin"];
+// shadow stores 99999 days for "never expires".
+const SHADOW_NEVER: i64 = 99999;
 
 #[derive(Serialize)]
 pub struct UserPasswdInfo {
     username: String,
     uid: String,
     gid: String,
+    gecos: String,
+    gecos_fields: GecosInfo,
     home_directory: String,
     login_shell: String,
+    primary_group: Option<String>,
+    groups: Vec<UserGroupInfo>,
+    password_aging: Option<PasswordAgingInfo>,
+    admin: AdminRightsInfo,
+}
+
+// GECOS is "full name,room,work phone,home phone,other" by convention.
+#[derive(Serialize)]
+pub struct GecosInfo {
+    full_name: Option<String>,
+    room: Option<String>,
+    work_phone: Option<String>,
+    home_phone: Option<String>,
+    other: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct UserGroupInfo {
+    name: String,
+    gid: u32,
+    primary: bool,
+}
+
+#[derive(Serialize)]
+pub struct PasswordAgingInfo {
+    password_status: String,
+    last_change: Option<String>,
+    min_days: Option<i64>,
+    max_days: Option<i64>,
+    warn_days: Option<i64>,
+    inactive_days: Option<i64>,
+    password_expires: Option<String>,
+    account_expires: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct AdminRightsInfo {
+    wheel: bool,
+    admin_groups: Vec<String>,
+    sudo: Option<bool>,
+    sudo_rules: Vec<String>,
+    polkit_admin: bool,
+    polkit_admin_identities: Vec<String>,
+}
+
+struct GroupEntry {
+    name: String,
+    gid: u32,
+    members: Vec<String>,
+}
+
+fn non_empty(value: Option<&str>) -> Option<String> {
+    value
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(String::from)
+}
+
+fn parse_gecos(gecos: &str) -> GecosInfo {
+    let mut parts = gecos.splitn(5, ',');
+    GecosInfo {
+        full_name: non_empty(parts.next()),
+        room: non_empty(parts.next()),
+        work_phone: non_empty(parts.next()),
+        home_phone: non_empty(parts.next()),
+        other: non_empty(parts.next()),
+    }
 }
 
 fn parse_passwd_line(line: &str) -> UserPasswdInfo {
-    let mut parts = line.split(':');
+    let mut parts = line.trim_end().split(':');
     let username = parts.next().unwrap_or_default().to_string();
     let _password = parts.next().unwrap_or_default();
     let uid = parts.next().unwrap_or_default().to_string();
     let gid = parts.next().unwrap_or_default().to_string();
-    let _gecos = parts.next().unwrap_or_default();
+    let gecos = parts.next().unwrap_or_default().to_string();
     let home_directory = parts.next().unwrap_or_default().to_string();
     let login_shell = parts.next().unwrap_or_default().to_string();
     UserPasswdInfo {
         username,
         uid,
         gid,
+        gecos_fields: parse_gecos(&gecos),
+        gecos,
         home_directory,
         login_shell,
+        primary_group: None,
+        groups: Vec::new(),
+        password_aging: None,
+        admin: AdminRightsInfo {
+            wheel: false,
+            admin_groups: Vec::new(),
+            sudo: None,
+            sudo_rules: Vec::new(),
+            polkit_admin: false,
+            polkit_admin_identities: Vec::new(),
+        },
+    }
+}
+
+// `getent group` also covers NSS sources (LDAP, sssd); /etc/group is the fallback.
+// Enumerating every group through NSS is slow on LDAP/sssd and often disabled,
+// so memberships come from `id` (getgrouplist) and this is only the fallback.
+fn group_entries() -> Vec<GroupEntry> {
+    read_file_string("/etc/group")
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split(':');
+            let name = parts.next()?.to_string();
+            let _password = parts.next()?;
+            let gid = parts.next()?.parse().ok()?;
+            let members = parts
+                .next()
+                .unwrap_or_default()
+                .split(',')
+                .map(str::trim)
+                .filter(|member| !member.is_empty())
+                .map(String::from)
+                .collect();
+            Some(GroupEntry { name, gid, members })
+        })
+        .collect()
+}
+
+fn id_groups(username: &str, primary_gid: &str) -> Option<Vec<UserGroupInfo>> {
+    let gids = run_command_optional("id", &["-G", "--", username])?;
+    let names = run_command_optional("id", &["-Gn", "--", username])?;
+    let gids: Vec<u32> = gids
+        .split_whitespace()
+        .map(|gid| gid.parse().ok())
+        .collect::<Option<_>>()?;
+    let names: Vec<&str> = names.split_whitespace().collect();
+    if gids.len() != names.len() {
+        return None;
+    }
+    let primary_gid: Option<u32> = primary_gid.parse().ok();
+    let mut seen = BTreeSet::new();
+    let mut result: Vec<UserGroupInfo> = gids
+        .into_iter()
+        .zip(names)
+        .filter(|(gid, _)| seen.insert(*gid))
+        .map(|(gid, name)| UserGroupInfo {
+            name: name.to_string(),
+            gid,
+            primary: Some(gid) == primary_gid,
+        })
+        .collect();
+    result.sort_by_key(|group| (!group.primary, group.gid));
+    Some(result)
+}
+
+fn user_groups(username: &str, primary_gid: &str, groups: &[GroupEntry]) -> Vec<UserGroupInfo> {
+    let primary_gid: Option<u32> = primary_gid.parse().ok();
+    let mut seen = BTreeSet::new();
+    let mut result: Vec<UserGroupInfo> = groups
+        .iter()
+        .filter(|group| {
+            Some(group.gid) == primary_gid || group.members.iter().any(|m| m == username)
+        })
+        .filter(|group| seen.insert(group.gid))
+        .map(|group| UserGroupInfo {
+            name: group.name.clone(),
+            gid: group.gid,
+            primary: Some(group.gid) == primary_gid,
+        })
+        .collect();
+    result.sort_by_key(|group| (!group.primary, group.gid));
+    result
+}
+
+// Days since the epoch to a "YYYY-MM-DD" civil date (proleptic Gregorian).
+fn days_to_date(days: i64) -> String {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+// shadow(5): name:password:lastchg:min:max:warn:inactive:expire:reserved, in days.
+fn parse_shadow_line(line: &str) -> Option<PasswordAgingInfo> {
+    let fields: Vec<&str> = line.trim_end().split(':').collect();
+    if fields.len() < 8 {
+        return None;
+    }
+    let number = |index: usize| fields[index].parse::<i64>().ok();
+    let password = fields[1];
+    let password_status = if password.is_empty() {
+        "none"
+    } else if password.starts_with('!') || password.starts_with('*') {
+        "locked"
+    } else {
+        "set"
+    };
+    let last_change = number(2);
+    let max_days = number(4);
+    let password_expires = match (last_change, max_days) {
+        (Some(changed), Some(max)) if changed > 0 && max < SHADOW_NEVER => {
+            Some(days_to_date(changed + max))
+        }
+        _ => None,
+    };
+    Some(PasswordAgingInfo {
+        password_status: password_status.to_string(),
+        // 0 means "must change at next login" rather than a real date.
+        last_change: last_change.filter(|days| *days > 0).map(days_to_date),
+        min_days: number(3),
+        max_days,
+        warn_days: number(5),
+        inactive_days: number(6),
+        password_expires,
+        account_expires: number(7).map(days_to_date),
+    })
+}
+
+// Shadow data is only readable by root (or the shadow group).
+fn password_aging(username: &str) -> Option<PasswordAgingInfo> {
+    let line = run_command_optional("getent", &["shadow", username]).or_else(|| {
+        read_file_optional("/etc/shadow")?
+            .lines()
+            .find(|line| line.split(':').next() == Some(username))
+            .map(String::from)
+    })?;
+    parse_shadow_line(&line)
+}
+
+// Reads sudoers with its #include/@includedir files, joining "\" continuations.
+fn sudoers_lines(path: &str, depth: usize, lines: &mut Vec<String>) -> bool {
+    let Some(text) = read_file_optional(path) else {
+        return false;
+    };
+    let text = text.replace("\\\n", " ");
+    for line in text.lines() {
+        let line = line.trim();
+        let include = line
+            .strip_prefix("@include")
+            .or_else(|| line.strip_prefix("#include"));
+        if let Some(include) = include {
+            if depth >= 8 {
+                continue;
+            }
+            if let Some(dir) = include.strip_prefix("dir") {
+                // sudo skips files containing '.' or ending in '~' in include dirs.
+                for file in list_dir_sorted(dir.trim()) {
+                    let name = file
+                        .file_name()
+                        .map(|name| name.to_string_lossy().into_owned())
+                        .unwrap_or_default();
+                    if !name.contains('.') && !name.ends_with('~') {
+                        sudoers_lines(&file.to_string_lossy(), depth + 1, lines);
+                    }
+                }
+            } else {
+                sudoers_lines(include.trim(), depth + 1, lines);
+            }
+            continue;
+        }
+        let line = strip_sudoers_comment(line).trim();
+        if !line.is_empty() {
+            lines.push(line.to_string());
+        }
+    }
+    true
+}
+
+// '#' starts a comment unless it is followed by a digit, as in the "#uid" and
+// "%#gid" user specs.
+fn strip_sudoers_comment(line: &str) -> &str {
+    let comment = line.char_indices().find(|(i, c)| {
+        *c == '#'
+            && !line[i + 1..]
+                .chars()
+                .next()
+                .is_some_and(|next| next.is_ascii_digit())
+    });
+    match comment {
+        Some((i, _)) => &line[..i],
+        None => line,
+    }
+}
+
+// Matches rule lines whose user list names the user, one of their groups or ALL.
+// User_Alias definitions are not expanded.
+fn sudo_rules(username: &str, uid: &str, groups: &[UserGroupInfo]) -> Option<Vec<String>> {
+    let mut lines = Vec::new();
+    if !sudoers_lines(SUDOERS, 0, &mut lines) {
+        return None;
+    }
+    let matches = |spec: &str| {
+        let spec = spec.trim();
+        if spec == "ALL" || spec == username || spec.strip_prefix('#') == Some(uid) {
+            return true;
+        }
+        match spec.strip_prefix('%') {
+            Some(group) => match group.strip_prefix('#') {
+                Some(gid) => groups.iter().any(|g| g.gid.to_string() == gid),
+                None => groups.iter().any(|g| g.name == group),
+            },
+            None => false,
+        }
+    };
+    Some(
+        lines
+            .into_iter()
+            .filter(|line| {
+                let first = line.split_whitespace().next().unwrap_or_default();
+                !first.starts_with("Defaults") && !first.ends_with("_Alias")
+            })
+            .filter(|line| {
+                let users = line.split_whitespace().next().unwrap_or_default();
+                users.split(',').any(matches)
+            })
+            .collect(),
+    )
+}
+
+// Identities polkit treats as administrators: the return values of
+// polkit.addAdminRule() in rules files, or AdminIdentities in the legacy
+// localauthority configuration.
+fn polkit_admin_identities() -> Vec<String> {
+    let mut identities = Vec::new();
+    let mut seen_files = BTreeSet::new();
+    for dir in POLKIT_RULES_DIRS {
+        for path in list_dir_sorted(dir) {
+            // A file in /etc overrides the packaged one with the same name.
+            let name = path.file_name().map(|name| name.to_os_string());
+            if !seen_files.insert(name) {
+                continue;
+            }
+            let text = read_file_optional(&path).unwrap_or_default();
+            for block in text.split("addAdminRule").skip(1) {
+                let block = block.split("});").next().unwrap_or_default();
+                for part in block.split('"').skip(1).step_by(2) {
+                    if part.starts_with("unix-group:") || part.starts_with("unix-user:") {
+                        identities.push(part.to_string());
+                    }
+                }
+            }
+        }
+    }
+    for path in list_dir_sorted(POLKIT_LOCALAUTHORITY_DIR) {
+        let text = read_file_optional(&path).unwrap_or_default();
+        for line in text.lines() {
+            if let Some(value) = line.trim().strip_prefix("AdminIdentities=") {
+                identities.extend(
+                    value
+                        .split(';')
+                        .map(str::trim)
+                        .filter(|identity| !identity.is_empty())
+                        .map(String::from),
+                );
+            }
+        }
+    }
+    identities.sort();
+    identities.dedup();
+    identities
+}
+
+fn admin_rights(user: &UserPasswdInfo) -> AdminRightsInfo {
+    let admin_groups: Vec<String> = user
+        .groups
+        .iter()
+        .filter(|group| ADMIN_GROUPS.contains(&group.name.as_str()))
+        .map(|group| group.name.clone())
+        .collect();
+    let sudo_rules = sudo_rules(&user.username, &user.uid, &user.groups);
+    let polkit_admin_identities = polkit_admin_identities();
+    let polkit_admin = user.uid == "0"
+        || polkit_admin_identities
+            .iter()
+            .any(|identity| match identity.split_once(':') {
+                Some(("unix-user", name)) => name == user.username || name == user.uid,
+                Some(("unix-group", name)) => user.groups.iter().any(|g| g.name == name),
+                _ => false,
+            });
+    AdminRightsInfo {
+        wheel: admin_groups.iter().any(|group| group == "wheel"),
+        admin_groups,
+        sudo: sudo_rules.as_ref().map(|rules| !rules.is_empty()),
+        sudo_rules: sudo_rules.unwrap_or_default(),
+        polkit_admin,
+        polkit_admin_identities,
     }
 }
 
 pub fn user_passwd_info() -> UserPasswdInfo {
     let user = current_username();
     let line = run_command_string("getent", &["passwd", &user]);
-    parse_passwd_line(&line)
+    let mut info = parse_passwd_line(&line);
+    info.groups = id_groups(&info.username, &info.gid)
+        .unwrap_or_else(|| user_groups(&info.username, &info.gid, &group_entries()));
+    info.primary_group = info
+        .groups
+        .iter()
+        .find(|group| group.primary)
+        .map(|group| group.name.clone());
+    info.password_aging = password_aging(&info.username);
+    info.admin = admin_rights(&info);
+    info
 }
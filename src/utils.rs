@@ -1,5 +1,12 @@
 use std::env;
-use std::process::Command;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde_json::Value;
 
 pub fn run_command_string(cmd: &str, args: &[&str]) -> String {
     let output = match Command::new(cmd).args(args).output() {
@@ -26,6 +33,38 @@ pub fn run_command_optional(cmd: &str, args: &[&str]) -> Option<String> {
     }
 }
 
+// Like run_command_optional, but kills the command if it has not exited within
+// `timeout` (e.g. a statfs stuck on an unreachable mount).
+pub fn run_command_timeout(cmd: &str, args: &[&str], timeout: Duration) -> Option<String> {
+    let mut child = Command::new(cmd)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+    let deadline = Instant::now() + timeout;
+    loop {
+        if child.try_wait().ok()?.is_some() {
+            break;
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            return None;
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    let output = child.wait_with_output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let text = String::from_utf8_lossy(&output.stdout)
+        .trim_end()
+        .to_string();
+    (!text.is_empty()).then_some(text)
+}
+
 pub fn command_colon_field(cmd: &str, args: &[&str], label: &str) -> String {
     let output = run_command_string(cmd, args);
     let prefix = if label.ends_with(':') {
@@ -54,3 +93,139 @@ pub fn current_username() -> String {
 pub fn env_var_opt(name: &str) -> Option<String> {
     env::var(name).ok()
 }
+
+pub fn read_file_optional(path: impl AsRef<Path>) -> Option<String> {
+    let text = fs::read_to_string(path).ok()?;
+    let text = text.trim_end().to_string();
+    if text.is_empty() {
+        None
+    } else {
+        Some(text)
+    }
+}
+
+pub fn read_file_string(path: impl AsRef<Path>) -> String {
+    read_file_optional(path).unwrap_or_default()
+}
+
+pub fn list_dir_sorted(path: impl AsRef<Path>) -> Vec<PathBuf> {
+    let mut entries: Vec<PathBuf> = match fs::read_dir(path) {
+        Ok(entries) => entries.filter_map(|e| e.ok()).map(|e| e.path()).collect(),
+        Err(_) => return Vec::new(),
+    };
+    entries.sort();
+    entries
+}
+
+pub fn path_string(path: impl AsRef<Path>) -> String {
+    path.as_ref().to_string_lossy().into_owned()
+}
+
+pub fn run_command_with_input(cmd: &str, args: &[&str], input: &[u8]) -> Option<Vec<u8>> {
+    let mut child = Command::new(cmd)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+    let mut stdin = child.stdin.take()?;
+    let output = thread::scope(|scope| {
+        scope.spawn(move || {
+            let _ = stdin.write_all(input);
+        });
+        child.wait_with_output()
+    })
+    .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(output.stdout)
+}
+
+pub fn file_mtime_secs(path: impl AsRef<Path>) -> Option<u64> {
+    let modified = fs::metadata(path).ok()?.modified().ok()?;
+    modified.duration_since(UNIX_EPOCH).ok().map(|d| d.as_secs())
+}
+
+pub fn find_in_path(name: &str) -> Option<PathBuf> {
+    let path = env::var("PATH").unwrap_or_default();
+    path.split(':')
+        .filter(|dir| !dir.is_empty())
+        .chain(["/usr/bin", "/usr/sbin", "/bin", "/sbin"])
+        .map(|dir| Path::new(dir).join(name))
+        .find(|candidate| candidate.is_file())
+}
+
+// Masks all but the last four characters of a serial number.
+pub fn redact_serial(value: &str) -> String {
+    let count = value.chars().count();
+    let hidden = count.saturating_sub(4);
+    let tail: String = value.chars().skip(hidden).collect();
+    format!("{}{tail}", "*".repeat(hidden))
+}
+
+pub fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+// A trimmed, non-empty string at a JSON pointer ("/a/b").
+pub fn json_str(value: &Value, pointer: &str) -> Option<String> {
+    value
+        .pointer(pointer)
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(String::from)
+}
+
+pub fn json_u64(value: &Value, pointer: &str) -> Option<u64> {
+    value.pointer(pointer).and_then(Value::as_u64)
+}
+
+pub fn json_i64(value: &Value, pointer: &str) -> Option<i64> {
+    value.pointer(pointer).and_then(Value::as_i64)
+}
+
+pub fn json_f64(value: &Value, pointer: &str) -> Option<f64> {
+    value.pointer(pointer).and_then(Value::as_f64)
+}
+
+pub fn json_bool(value: &Value, pointer: &str) -> Option<bool> {
+    value.pointer(pointer).and_then(Value::as_bool)
+}
+
+// Decodes "\ooo" octal escapes, as used by /proc/self/mountinfo, fstab and mtree.
+pub fn unescape_octal(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let digits = bytes.get(i + 1..i + 4).unwrap_or_default();
+        if bytes[i] == b'\\'
+            && digits.len() == 3
+            && digits.iter().all(|d| (b'0'..=b'7').contains(d))
+        {
+            out.push(
+                digits
+                    .iter()
+                    .fold(0u8, |acc, d| acc.wrapping_mul(8) + (d - b'0')),
+            );
+            i += 4;
+            continue;
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+// Formats a dev_t as "major:minor", the form lsblk and /sys/.../dev use.
+pub fn dev_maj_min(dev: u64) -> String {
+    let major = ((dev >> 8) & 0xfff) | ((dev >> 32) & !0xfff);
+    let minor = (dev & 0xff) | ((dev >> 12) & !0xff);
+    format!("{major}:{minor}")
+}
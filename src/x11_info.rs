@@ -1,5 +1,8 @@
+use std::collections::BTreeMap;
+
 use serde::Serialize;
 
+use crate::edid::{decode_edid_hex, EdidInfo};
 use crate::utils::{run_command_optional, trim_tree_prefix};
 
 #[derive(Serialize)]
@@ -9,6 +12,19 @@ pub struct XinputDeviceInfo {
     role: Option<String>,
     device_type: Option<String>,
     attached_to: Option<String>,
+    properties: Option<XinputPropertiesInfo>,
+}
+
+#[derive(Serialize)]
+pub struct XinputPropertiesInfo {
+    enabled: Option<bool>,
+    accel_speed: Option<f64>,
+    accel_profile: Option<String>,
+    tapping: Option<bool>,
+    natural_scrolling: Option<bool>,
+    calibration_matrix: Option<Vec<f64>>,
+    transformation_matrix: Option<Vec<f64>>,
+    all: BTreeMap<String, String>,
 }
 
 fn parse_xinput_bracket(value: &str) -> (Option<String>, Option<String>, Option<String>) {
@@ -17,11 +33,11 @@ fn parse_xinput_bracket(value: &str) -> (Option<String>, Option<String>, Option<
     let mut attached_to = None;
 
     let mut content = value.trim();
-    if let Some(lb) = content.rfind('(') {
-        if let Some(rb) = content[lb + 1..].find(')') {
-            attached_to = Some(content[lb + 1..lb + 1 + rb].trim().to_string());
-            content = content[..lb].trim_end();
-        }
+    if let Some(lb) = content.rfind('(')
+        && let Some(rb) = content[lb + 1..].find(')')
+    {
+        attached_to = Some(content[lb + 1..lb + 1 + rb].trim().to_string());
+        content = content[..lb].trim_end();
     }
 
     let mut parts = content.split_whitespace();
@@ -55,14 +71,14 @@ fn parse_xinput_list(output: &str) -> Vec<XinputDeviceInfo> {
         let mut role = None;
         let mut device_type = None;
         let mut attached_to = None;
-        if let Some(lb) = line.find('[') {
-            if let Some(rb) = line[lb + 1..].find(']') {
-                let bracket = &line[lb + 1..lb + 1 + rb];
-                let parsed = parse_xinput_bracket(bracket);
-                role = parsed.0;
-                device_type = parsed.1;
-                attached_to = parsed.2;
-            }
+        if let Some(lb) = line.find('[')
+            && let Some(rb) = line[lb + 1..].find(']')
+        {
+            let bracket = &line[lb + 1..lb + 1 + rb];
+            let parsed = parse_xinput_bracket(bracket);
+            role = parsed.0;
+            device_type = parsed.1;
+            attached_to = parsed.2;
         }
 
         devices.push(XinputDeviceInfo {
@@ -71,6 +87,7 @@ fn parse_xinput_list(output: &str) -> Vec<XinputDeviceInfo> {
             role,
             device_type,
             attached_to,
+            properties: None,
         });
     }
     devices
@@ -81,11 +98,104 @@ pub struct XinputInfo {
     devices: Option<Vec<XinputDeviceInfo>>,
 }
 
+// "\tlibinput Accel Speed (330):\t0.000000"
+fn parse_xinput_props(output: &str) -> XinputPropertiesInfo {
+    let all: BTreeMap<String, String> = output
+        .lines()
+        .filter(|line| line.starts_with('\t'))
+        .filter_map(|line| {
+            let (name, value) = line.split_once(":\t").or_else(|| line.split_once(": "))?;
+            let name = match name.rfind(" (") {
+                Some(pos) if name.ends_with(')') => &name[..pos],
+                _ => name,
+            };
+            Some((name.trim().to_string(), value.trim().to_string()))
+        })
+        .collect();
+    let flag = |name: &str| all.get(name).map(|value| value == "1");
+    let numbers = |name: &str| -> Option<Vec<f64>> {
+        all.get(name)?
+            .split(',')
+            .map(|value| value.trim().parse().ok())
+            .collect()
+    };
+    // The enabled-profile property is a flag list in the order adaptive, flat, custom.
+    let accel_profile = numbers("libinput Accel Profile Enabled").and_then(|flags| {
+        ["adaptive", "flat", "custom"]
+            .iter()
+            .zip(flags)
+            .find(|(_, flag)| *flag == 1.0)
+            .map(|(name, _)| name.to_string())
+    });
+    XinputPropertiesInfo {
+        enabled: flag("Device Enabled"),
+        accel_speed: all
+            .get("libinput Accel Speed")
+            .and_then(|value| value.parse().ok()),
+        accel_profile,
+        tapping: flag("libinput Tapping Enabled"),
+        natural_scrolling: flag("libinput Natural Scrolling Enabled"),
+        calibration_matrix: numbers("libinput Calibration Matrix"),
+        transformation_matrix: numbers("Coordinate Transformation Matrix"),
+        all,
+    }
+}
+
 fn xinput_info() -> XinputInfo {
-    let devices = run_command_optional("xinput", &["list"]).map(|out| parse_xinput_list(&out));
+    let devices = run_command_optional("xinput", &["list"]).map(|out| {
+        let mut devices = parse_xinput_list(&out);
+        for device in &mut devices {
+            device.properties = run_command_optional("xinput", &["list-props", &device.id])
+                .map(|props| parse_xinput_props(&props));
+        }
+        devices
+    });
     XinputInfo { devices }
 }
 
+#[derive(Serialize)]
+pub struct XkbInfo {
+    rules: Option<String>,
+    model: Option<String>,
+    layouts: Vec<String>,
+    variants: Vec<String>,
+    options: Vec<String>,
+}
+
+fn parse_setxkbmap_query(output: &str) -> XkbInfo {
+    let field = |label: &str| {
+        output.lines().find_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            (name.trim() == label).then(|| value.trim().to_string())
+        })
+    };
+    // Layouts and variants are positional, so empty variants are kept.
+    let list = |label: &str| -> Vec<String> {
+        field(label)
+            .map(|value| {
+                value
+                    .split(',')
+                    .map(|item| item.trim().to_string())
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    XkbInfo {
+        rules: field("rules"),
+        model: field("model"),
+        layouts: list("layout"),
+        variants: list("variant"),
+        options: list("options")
+            .into_iter()
+            .filter(|option| !option.is_empty())
+            .collect(),
+    }
+}
+
+fn xkb_info() -> Option<XkbInfo> {
+    run_command_optional("setxkbmap", &["-query"]).map(|out| parse_setxkbmap_query(&out))
+}
+
 #[derive(Serialize)]
 pub struct XrandrMonitorInfo {
     index: u32,
@@ -93,9 +203,39 @@ pub struct XrandrMonitorInfo {
     geometry: String,
 }
 
+#[derive(Serialize)]
+pub struct XrandrOutputInfo {
+    name: String,
+    connection: String,
+    primary: bool,
+    position_x: Option<i64>,
+    position_y: Option<i64>,
+    rotation: Option<String>,
+    reflection: Option<String>,
+    physical_width_mm: Option<u64>,
+    physical_height_mm: Option<u64>,
+    current_mode: Option<XrandrModeInfo>,
+    preferred_mode: Option<XrandrModeInfo>,
+    modes: Vec<XrandrModeInfo>,
+    edid: Option<EdidInfo>,
+}
+
+#[derive(Clone, Serialize)]
+pub struct XrandrModeInfo {
+    name: String,
+    id: Option<String>,
+    width: Option<u64>,
+    height: Option<u64>,
+    refresh_hz: Option<f64>,
+    interlaced: bool,
+    current: bool,
+    preferred: bool,
+}
+
 #[derive(Serialize)]
 pub struct XrandrInfo {
     monitors: Option<Vec<XrandrMonitorInfo>>,
+    outputs: Option<Vec<XrandrOutputInfo>>,
 }
 
 fn parse_xrandr_listmonitors(output: &str) -> Vec<XrandrMonitorInfo> {
@@ -113,7 +253,7 @@ fn parse_xrandr_listmonitors(output: &str) -> Vec<XrandrMonitorInfo> {
             Ok(value) => value,
             Err(_) => continue,
         };
-        let mut parts: Vec<&str> = rest.trim().split_whitespace().collect();
+        let mut parts: Vec<&str> = rest.split_whitespace().collect();
         if parts.len() < 2 {
             continue;
         }
@@ -128,73 +268,432 @@ fn parse_xrandr_listmonitors(output: &str) -> Vec<XrandrMonitorInfo> {
     monitors
 }
 
-fn xrandr_info() -> XrandrInfo {
+const XRANDR_ROTATIONS: [&str; 4] = ["normal", "left", "inverted", "right"];
+
+fn parse_xrandr_geometry(value: &str) -> Option<(i64, i64)> {
+    let (_, offsets) = value.split_once('+')?;
+    let (x, y) = offsets.split_once('+')?;
+    Some((x.parse().ok()?, y.parse().ok()?))
+}
+
+// "eDP-1 connected primary 1920x1080+0+0 (0x46) normal (normal left ...) 344mm x 194mm"
+fn parse_xrandr_output_header(line: &str) -> Option<XrandrOutputInfo> {
+    let (name, rest) = line.split_once(' ')?;
+    let connection = if rest.starts_with("connected") {
+        "connected"
+    } else if rest.starts_with("disconnected") {
+        "disconnected"
+    } else if rest.starts_with("unknown connection") {
+        "unknown"
+    } else {
+        return None;
+    };
+    // The supported rotations list is the only part in parentheses containing spaces.
+    let (state, size) = match rest.find(" (normal") {
+        Some(pos) => (
+            &rest[..pos],
+            rest[pos..].split_once(')').map_or("", |(_, s)| s),
+        ),
+        None => (rest, ""),
+    };
+    let tokens: Vec<&str> = state.split_whitespace().collect();
+    let position = tokens.iter().find_map(|token| parse_xrandr_geometry(token));
+    let rotation = tokens
+        .iter()
+        .find(|token| XRANDR_ROTATIONS.contains(token))
+        .map(|token| token.to_string());
+    // " X and Y axis" contains " Y axis", so it has to be checked first.
+    let reflection = state
+        .find(" X and Y axis")
+        .or_else(|| state.find(" X axis"))
+        .or_else(|| state.find(" Y axis"))
+        .map(|pos| state[pos..].trim().to_string());
+    let size_tokens: Vec<&str> = size.split_whitespace().collect();
+    let millimetres = |token: Option<&&str>| -> Option<u64> {
+        token?.strip_suffix("mm")?.parse().ok().filter(|mm| *mm > 0)
+    };
+    Some(XrandrOutputInfo {
+        name: name.to_string(),
+        connection: connection.to_string(),
+        primary: tokens.contains(&"primary"),
+        position_x: position.map(|(x, _)| x),
+        position_y: position.map(|(_, y)| y),
+        rotation,
+        reflection,
+        physical_width_mm: millimetres(size_tokens.first()),
+        physical_height_mm: millimetres(size_tokens.get(2)),
+        current_mode: None,
+        preferred_mode: None,
+        modes: Vec::new(),
+        edid: None,
+    })
+}
+
+// "  1920x1080 (0x46) 141.000MHz -HSync -VSync *current +preferred"
+fn parse_xrandr_mode(line: &str) -> Option<XrandrModeInfo> {
+    let mut tokens = line.split_whitespace();
+    let name = tokens.next()?;
+    let (width, height) = name.split_once('x')?;
+    let width = width.parse().ok()?;
+    let rest: Vec<&str> = tokens.collect();
+    let digits: String = height.chars().take_while(char::is_ascii_digit).collect();
+    Some(XrandrModeInfo {
+        name: name.to_string(),
+        id: rest
+            .iter()
+            .find(|token| token.starts_with("(0x"))
+            .map(|token| token.trim_matches(|c| c == '(' || c == ')').to_string()),
+        width: Some(width),
+        height: digits.parse().ok(),
+        refresh_hz: None,
+        interlaced: height.ends_with('i') || rest.contains(&"Interlace"),
+        current: rest.contains(&"*current"),
+        preferred: rest.contains(&"+preferred"),
+    })
+}
+
+fn finish_xrandr_output(
+    mut output: XrandrOutputInfo,
+    edid_hex: &str,
+    redact_serials: bool,
+) -> XrandrOutputInfo {
+    output.current_mode = output.modes.iter().find(|mode| mode.current).cloned();
+    output.preferred_mode = output.modes.iter().find(|mode| mode.preferred).cloned();
+    output.edid = decode_edid_hex(edid_hex, redact_serials);
+    output
+}
+
+fn parse_xrandr_verbose(output: &str, redact_serials: bool) -> Vec<XrandrOutputInfo> {
+    let mut outputs = Vec::new();
+    let mut current: Option<XrandrOutputInfo> = None;
+    let mut edid_hex = String::new();
+    let mut in_edid = false;
+    // Whether the h:/v: lines that follow belong to a mode that parsed.
+    let mut in_mode = false;
+    for line in output.lines() {
+        if !line.starts_with(char::is_whitespace) {
+            if let Some(done) = current.take() {
+                outputs.push(finish_xrandr_output(done, &edid_hex, redact_serials));
+            }
+            edid_hex.clear();
+            in_edid = false;
+            in_mode = false;
+            current = parse_xrandr_output_header(line);
+            continue;
+        }
+        let Some(output) = current.as_mut() else {
+            continue;
+        };
+        let trimmed = line.trim();
+        if line.starts_with('\t') {
+            // Properties are indented by one tab, their values by two.
+            if line.starts_with("\t\t") {
+                if in_edid {
+                    edid_hex.push_str(trimmed);
+                }
+            } else {
+                in_edid = trimmed.starts_with("EDID:");
+            }
+            continue;
+        }
+        in_edid = false;
+        if let Some(rest) = trimmed.strip_prefix("v:") {
+            // "v: height 1080 start 1083 end 1088 total 1116 clock 60.05Hz"
+            let refresh = rest
+                .split_whitespace()
+                .skip_while(|token| *token != "clock")
+                .nth(1)
+                .and_then(|value| value.trim_end_matches("Hz").parse().ok());
+            if in_mode && let Some(mode) = output.modes.last_mut() {
+                mode.refresh_hz = refresh;
+            }
+        } else if !trimmed.starts_with("h:") {
+            // Custom modes need not be named WxH; skip them and their clocks.
+            let mode = parse_xrandr_mode(trimmed);
+            in_mode = mode.is_some();
+            output.modes.extend(mode);
+        }
+    }
+    if let Some(done) = current {
+        outputs.push(finish_xrandr_output(done, &edid_hex, redact_serials));
+    }
+    outputs
+}
+
+fn xrandr_info(redact_serials: bool) -> XrandrInfo {
     let monitors = run_command_optional("xrandr", &["--listmonitors"])
         .map(|out| parse_xrandr_listmonitors(&out));
-    XrandrInfo { monitors }
+    let outputs = run_command_optional("xrandr", &["--verbose"])
+        .map(|out| parse_xrandr_verbose(&out, redact_serials));
+    XrandrInfo { monitors, outputs }
+}
+
+#[derive(Serialize)]
+pub struct XdpyScreenInfo {
+    number: u32,
+    width_px: Option<u64>,
+    height_px: Option<u64>,
+    width_mm: Option<u64>,
+    height_mm: Option<u64>,
+    dpi_x: Option<f64>,
+    dpi_y: Option<f64>,
+    depth: Option<u32>,
+    depths: Vec<u32>,
 }
 
 #[derive(Serialize)]
 pub struct XdpyInfo {
-    dimensions: Option<String>,
-    resolution: Option<String>,
+    display_name: Option<String>,
+    version: Option<String>,
+    vendor: Option<String>,
+    vendor_release: Option<String>,
+    default_screen: Option<u32>,
+    screen_count: Option<u32>,
+    extensions: Vec<String>,
+    screens: Vec<XdpyScreenInfo>,
 }
 
-fn xdpy_info() -> XdpyInfo {
-    let output = run_command_optional("xdpyinfo", &[]).unwrap_or_default();
-    let mut dimensions = None;
-    let mut resolution = None;
+fn parse_pair<T: std::str::FromStr>(value: &str) -> Option<(T, T)> {
+    let (first, second) = value.split_once('x')?;
+    Some((first.trim().parse().ok()?, second.trim().parse().ok()?))
+}
+
+// "3840x2160 pixels (1016x571 millimeters)"
+fn parse_xdpy_dimensions(value: &str, screen: &mut XdpyScreenInfo) {
+    let mut tokens = value.split_whitespace();
+    if let Some((width, height)) = tokens.next().and_then(parse_pair) {
+        screen.width_px = Some(width);
+        screen.height_px = Some(height);
+    }
+    if let Some((width, height)) = tokens
+        .find(|token| token.starts_with('('))
+        .and_then(|token| parse_pair(token.trim_start_matches('(')))
+    {
+        screen.width_mm = Some(width);
+        screen.height_mm = Some(height);
+    }
+}
+
+fn parse_xdpyinfo(output: &str) -> XdpyInfo {
+    let mut info = XdpyInfo {
+        display_name: None,
+        version: None,
+        vendor: None,
+        vendor_release: None,
+        default_screen: None,
+        screen_count: None,
+        extensions: Vec::new(),
+        screens: Vec::new(),
+    };
+    let mut in_extensions = false;
     for line in output.lines() {
-        let line = line.trim();
-        if let Some(rest) = line.strip_prefix("dimensions:") {
-            let value = rest.trim();
-            if !value.is_empty() {
-                dimensions = Some(value.to_string());
-            }
+        // Extension names are the only indented lines before the first screen.
+        if in_extensions && line.starts_with("    ") {
+            info.extensions.push(line.trim().to_string());
             continue;
         }
-        if let Some(rest) = line.strip_prefix("resolution:") {
-            let value = rest.trim();
-            if !value.is_empty() {
-                resolution = Some(value.to_string());
+        in_extensions = false;
+        if let Some(rest) = line.strip_prefix("screen #") {
+            info.screens.push(XdpyScreenInfo {
+                number: rest
+                    .trim_end_matches(':')
+                    .trim()
+                    .parse()
+                    .unwrap_or_default(),
+                width_px: None,
+                height_px: None,
+                width_mm: None,
+                height_mm: None,
+                dpi_x: None,
+                dpi_y: None,
+                depth: None,
+                depths: Vec::new(),
+            });
+            continue;
+        }
+        let Some((label, value)) = line.trim().split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        let text = || Some(value.to_string()).filter(|value| !value.is_empty());
+        if let Some(screen) = info.screens.last_mut() {
+            match label {
+                "dimensions" => parse_xdpy_dimensions(value, screen),
+                "resolution" => {
+                    if let Some((x, y)) = value.split_whitespace().next().and_then(parse_pair) {
+                        screen.dpi_x = Some(x);
+                        screen.dpi_y = Some(y);
+                    }
+                }
+                "depth of root window" => {
+                    screen.depth = value.split_whitespace().next().and_then(|v| v.parse().ok());
+                }
+                _ if label.starts_with("depths") => {
+                    screen.depths = value
+                        .split(',')
+                        .filter_map(|depth| depth.trim().parse().ok())
+                        .collect();
+                }
+                _ => {}
             }
+            continue;
+        }
+        match label {
+            "name of display" => info.display_name = text(),
+            "version number" => info.version = text(),
+            "vendor string" => info.vendor = text(),
+            "vendor release number" => info.vendor_release = text(),
+            "default screen number" => info.default_screen = value.parse().ok(),
+            "number of screens" => info.screen_count = value.parse().ok(),
+            "number of extensions" => in_extensions = true,
+            _ => {}
         }
     }
-    XdpyInfo {
-        dimensions,
-        resolution,
-    }
+    info
+}
+
+fn xdpy_info() -> XdpyInfo {
+    parse_xdpyinfo(&run_command_optional("xdpyinfo", &[]).unwrap_or_default())
+}
+
+#[derive(Serialize)]
+pub struct XrdbResourceInfo {
+    pattern: String,
+    value: String,
+    components: Vec<String>,
+    loose_binding: bool,
+    application: Option<String>,
+    application_is_class: bool,
 }
 
 #[derive(Serialize)]
 pub struct XrdbInfo {
-    resources: Vec<String>,
+    resources: BTreeMap<String, String>,
+    entries: Vec<XrdbResourceInfo>,
+    xft_dpi: Option<f64>,
+}
+
+// Splits "XTerm*vt100.faceName" into ["XTerm", "*", "vt100", "faceName"]; a
+// tight binding ('.') only separates components while '*' is kept as one.
+fn xrdb_components(pattern: &str) -> Vec<String> {
+    let mut components = Vec::new();
+    let mut current = String::new();
+    for c in pattern.chars() {
+        if c == '.' || c == '*' {
+            if !current.is_empty() {
+                components.push(std::mem::take(&mut current));
+            }
+            if c == '*' && components.last().is_none_or(|last| last != "*") {
+                components.push("*".to_string());
+            }
+        } else {
+            current.push(c);
+        }
+    }
+    if !current.is_empty() {
+        components.push(current);
+    }
+    components
+}
+
+fn parse_xrdb_query(output: &str) -> XrdbInfo {
+    let mut resources = BTreeMap::new();
+    let mut entries = Vec::new();
+    for line in output.lines() {
+        let Some((pattern, value)) = line.split_once(':') else {
+            continue;
+        };
+        let pattern = pattern.trim();
+        if pattern.is_empty() || pattern.starts_with('!') {
+            continue;
+        }
+        let value = value.trim().to_string();
+        let components = xrdb_components(pattern);
+        let application = components
+            .first()
+            .filter(|first| *first != "*" && *first != "?" && components.len() > 1)
+            .cloned();
+        resources.insert(pattern.to_string(), value.clone());
+        entries.push(XrdbResourceInfo {
+            pattern: pattern.to_string(),
+            value,
+            loose_binding: pattern.contains('*'),
+            application_is_class: application
+                .as_deref()
+                .is_some_and(|app| app.starts_with(|c: char| c.is_ascii_uppercase())),
+            application,
+            components,
+        });
+    }
+    // Xft.dpi is what toolkits use for font scaling; "*dpi" patterns match it too.
+    let xft_dpi = ["Xft.dpi", "Xft*dpi", "*.dpi", "*dpi"]
+        .iter()
+        .find_map(|key| resources.get(*key))
+        .and_then(|value| value.parse().ok());
+    XrdbInfo {
+        resources,
+        entries,
+        xft_dpi,
+    }
 }
 
 fn xrdb_info() -> XrdbInfo {
-    let resources = run_command_optional("xrdb", &["-query"])
-        .unwrap_or_default()
-        .lines()
-        .map(str::trim)
-        .filter(|line| !line.is_empty())
-        .map(String::from)
-        .collect();
-    XrdbInfo { resources }
+    parse_xrdb_query(&run_command_optional("xrdb", &["-query"]).unwrap_or_default())
 }
 
 #[derive(Serialize)]
 pub struct X11Info {
     xinput: XinputInfo,
+    keyboard: Option<XkbInfo>,
     xrandr: XrandrInfo,
     xrdb: XrdbInfo,
     xdpyinfo: XdpyInfo,
 }
 
-pub fn x11_info() -> X11Info {
+pub fn x11_info(redact_serials: bool) -> X11Info {
     X11Info {
         xinput: xinput_info(),
-        xrandr: xrandr_info(),
+        keyboard: xkb_info(),
+        xrandr: xrandr_info(redact_serials),
         xrdb: xrdb_info(),
         xdpyinfo: xdpy_info(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const XRANDR_VERBOSE_FIXTURE: &str = "\
+Screen 0: minimum 320 x 200, current 1920 x 1080, maximum 16384 x 16384
+HDMI-1 connected primary 1920x1080+0+0 (0x46) normal (normal left inverted right x axis y axis) 600mm x 340mm
+\tBrightness: 1.0
+  1920x1080 (0x46) 148.500MHz +HSync +VSync *current +preferred
+        h: width  1920 start 2008 end 2052 total 2200 skew    0 clock  67.50KHz
+        v: height 1080 start 1084 end 1089 total 1125           clock  60.00Hz
+  my-custom (0x4a) 100.000MHz -HSync +VSync
+        h: width  1600 start 1648 end 1680 total 1760 skew    0 clock  56.82KHz
+        v: height  900 start  903 end  908 total  926           clock  61.36Hz
+  1280x720 (0x4b) 74.250MHz +HSync +VSync
+        h: width  1280 start 1390 end 1430 total 1650 skew    0 clock  45.00KHz
+        v: height  720 start  725 end  730 total  750           clock  50.00Hz
+DP-1 disconnected X and Y axis (normal left inverted right x axis y axis)
+";
+
+    #[test]
+    fn custom_mode_clocks_do_not_overwrite_previous_mode() {
+        let outputs = parse_xrandr_verbose(XRANDR_VERBOSE_FIXTURE, false);
+        assert_eq!(outputs.len(), 2);
+        let hdmi = &outputs[0];
+        assert!(hdmi.primary);
+        let modes: Vec<(&str, Option<f64>)> = hdmi
+            .modes
+            .iter()
+            .map(|mode| (mode.name.as_str(), mode.refresh_hz))
+            .collect();
+        assert_eq!(modes, [("1920x1080", Some(60.0)), ("1280x720", Some(50.0))]);
+        assert_eq!(hdmi.current_mode.as_ref().unwrap().name, "1920x1080");
+        assert_eq!(hdmi.physical_width_mm, Some(600));
+        assert_eq!(outputs[1].reflection.as_deref(), Some("X and Y axis"));
+    }
+}
@@ -16,6 +16,12 @@ pub struct XdgInfo {
     xdg_vtnr: Option<String>,
 }
 
+impl XdgInfo {
+    pub fn session_type(&self) -> Option<&str> {
+        self.xdg_session_type.as_deref()
+    }
+}
+
 pub fn xdg_info() -> XdgInfo {
     XdgInfo {
         xdg_cache_home: env_var_opt("XDG_CACHE_HOME"),